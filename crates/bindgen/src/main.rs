@@ -0,0 +1,113 @@
+//! Generates WinRT bindings to a file that can be checked into source control.
+//!
+//! The config file lists the types to generate using the same syntax as the `build!` macro and
+//! the generated code is the same as `build!` would produce:
+//!
+//! ```text
+//! // bindings.txt
+//! contracts { UniversalApiContract = 10 }
+//! metadata { "winmd" }
+//! windows::foundation::collections::*
+//! windows::ui::{Colors, text::FontWeights}
+//! ```
+//!
+//! ```text
+//! winrt-bindgen bindings.txt src/bindings.rs
+//! ```
+//!
+//! The paths in a `metadata` block are relative to the current directory, and without one the
+//! metadata is found as for `build!`. The metadata files that were loaded are listed as they're read.
+//!
+//! With `--split` the output is a directory with a file per namespace rather than a single file,
+//! and with `--features` each namespace is also gated behind a cargo feature whose `[features]`
+//! table is written to `features.toml` in the directory:
+//!
+//! ```text
+//! winrt-bindgen --split --features bindings.txt src/bindings
+//! ```
+
+use winrt_gen::{BuildLimits, Generator};
+
+const USAGE: &str = "usage: winrt-bindgen [--split [--features]] <config> <output>";
+
+struct Options {
+    config: String,
+    output: String,
+    split: bool,
+    features: bool,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Some(options) => options,
+        None => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(message) = run(&options) {
+        eprintln!("error: {}", message);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(args: I) -> Option<Options> {
+    let mut split = false;
+    let mut features = false;
+    let mut paths = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--split" => split = true,
+            "--features" => features = true,
+            _ if arg.starts_with("--") => return None,
+            _ => paths.push(arg),
+        }
+    }
+
+    // Features gate the files of each namespace so they require a file per namespace.
+    if features && !split {
+        return None;
+    }
+
+    match paths.as_slice() {
+        [config, output] => Some(Options {
+            config: config.clone(),
+            output: output.clone(),
+            split,
+            features,
+        }),
+        _ => None,
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let config = &options.config;
+    let output = &options.output;
+
+    let source = std::fs::read_to_string(config)
+        .map_err(|error| format!("failed to read '{}': {}", config, error))?;
+
+    let build: BuildLimits = syn::parse_str(&source).map_err(|error| error.to_string())?;
+
+    let reader = build.reader().map_err(|error| error.to_string())?;
+
+    for file in &reader.files {
+        eprintln!("loaded {}", file.path.display());
+    }
+
+    let limits = build.to_limits(reader).map_err(|error| error.to_string())?;
+
+    let generator = Generator::new(reader)
+        .limits(limits)
+        .features(options.features);
+
+    let result = if options.split {
+        generator.write_to_dir(output)
+    } else {
+        generator.write_to(output)
+    };
+
+    result.map_err(|error| format!("failed to write '{}': {}", output, error))
+}
@@ -0,0 +1,37 @@
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+// Hashes the generator's sources so that bindings cached by an earlier build of the generator
+// aren't reused when the generator changes without its version changing, such as when it's a git
+// or path dependency.
+fn main() {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+
+    for dir in &["src", "macros/src"] {
+        println!("cargo:rerun-if-changed={}", dir);
+        hash_dir(Path::new(dir), &mut hasher);
+    }
+
+    println!(
+        "cargo:rustc-env=WINRT_GEN_SOURCE_HASH={:016x}",
+        hasher.finish()
+    );
+}
+
+fn hash_dir(dir: &Path, hasher: &mut impl Hasher) {
+    let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+        Err(_) => return,
+    };
+
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            hash_dir(&path, hasher);
+        } else if let Ok(bytes) = std::fs::read(&path) {
+            path.hash(hasher);
+            bytes.hash(hasher);
+        }
+    }
+}
@@ -0,0 +1,895 @@
+use crate::*;
+use std::convert::{TryFrom, TryInto};
+use syn::spanned::Spanned;
+
+/// The types, renames, members, exclusions, contracts, extern namespaces, metadata and deployment
+/// selected with the `build!` syntax
+pub struct BuildLimits {
+    pub types: std::collections::BTreeSet<TypesDeclaration>,
+    pub exclusions: std::collections::BTreeSet<TypesDeclaration>,
+    pub renames: Vec<RenameDeclaration>,
+    pub members: Vec<MembersDeclaration>,
+    pub contracts: Vec<ContractDeclaration>,
+    pub externs: Vec<ExternDeclaration>,
+    /// The winmd files and directories of winmd files to load instead of the build's metadata
+    pub metadata: Vec<syn::LitStr>,
+    pub deploy: DeployDeclaration,
+}
+
+impl BuildLimits {
+    /// The metadata to resolve the selected types against
+    ///
+    /// This is the build's metadata unless paths are given in a `metadata` block, in which case
+    /// they're relative to the package's directory.
+    pub fn reader(&self) -> Result<&'static winmd::TypeReader, syn::Error> {
+        if self.metadata.is_empty() {
+            return winmd::TypeReader::try_from_build().map_err(|error| {
+                syn::Error::new(proc_macro2::Span::call_site(), error.to_string())
+            });
+        }
+
+        let dir = std::env::var_os("CARGO_MANIFEST_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_default();
+
+        let mut paths = Vec::with_capacity(self.metadata.len());
+
+        for path in &self.metadata {
+            let full = dir.join(path.value());
+
+            if !full.exists() {
+                return Err(syn::Error::new(
+                    path.span(),
+                    format!("Could not find metadata at {:?}", full),
+                ));
+            }
+
+            paths.push(full);
+        }
+
+        winmd::TypeReader::from_paths(&paths)
+            .map_err(|error| syn::Error::new(proc_macro2::Span::call_site(), error.to_string()))
+    }
+
+    /// Resolves the selected namespaces and types against the metadata
+    pub fn to_limits(
+        self,
+        reader: &'static winmd::TypeReader,
+    ) -> Result<TypeLimits<'static>, syn::Error> {
+        let is_foundation =
+            self.types.is_empty() && self.renames.is_empty() && self.members.is_empty();
+
+        let mut limits = TypeLimits::new(reader);
+
+        // The contracts are inserted first since the types selected by name must be available in
+        // them.
+        for contract in self.contracts {
+            let syntax = contract.syntax;
+            limits
+                .insert_contract(&contract.contract, contract.version)
+                .map_err(|contract| {
+                    syn::Error::new_spanned(
+                        syntax,
+                        format!("'{}' is not a known contract", contract),
+                    )
+                })?;
+        }
+
+        for limit in self.types {
+            let types = limit.types;
+            let syntax = limit.syntax;
+            limits
+                .insert(types)
+                .map_err(|error| limit_error(&syntax, error, reader))?;
+        }
+
+        for RenameDeclaration { rename, syntax } in self.renames {
+            // The foundation namespaces are provided by the `winrt` crate rather than generated so
+            // their types can't be renamed.
+            if !is_foundation
+                && FOUNDATION_NAMESPACES
+                    .iter()
+                    .any(|namespace| namespace.to_lowercase() == rename.namespace)
+            {
+                return Err(syn::Error::new_spanned(
+                    syntax,
+                    format!("'{}' types can't be renamed", rename.namespace),
+                ));
+            }
+
+            limits
+                .insert_rename(rename)
+                .map_err(|error| limit_error(&syntax, error, reader))?;
+        }
+
+        for MembersDeclaration { members, syntax } in self.members {
+            // The foundation namespaces are provided by the `winrt` crate in full.
+            if !is_foundation
+                && FOUNDATION_NAMESPACES
+                    .iter()
+                    .any(|namespace| namespace.to_lowercase() == members.namespace)
+            {
+                return Err(syn::Error::new_spanned(
+                    syntax,
+                    format!("'{}' types can't be limited to members", members.namespace),
+                ));
+            }
+
+            limits
+                .insert_members(members)
+                .map_err(|error| limit_error(&syntax, error, reader))?;
+        }
+
+        for exclusion in self.exclusions {
+            let syntax = exclusion.syntax;
+            limits
+                .exclude(exclusion.types)
+                .map_err(|error| limit_error(&syntax, error, reader))?;
+        }
+
+        for ExternDeclaration {
+            namespace,
+            path,
+            syntax,
+        } in self.externs
+        {
+            if FOUNDATION_NAMESPACES
+                .iter()
+                .any(|foundation| foundation.to_lowercase() == namespace)
+            {
+                return Err(syn::Error::new_spanned(
+                    syntax,
+                    format!("'{}' is already provided by the `winrt` crate", namespace),
+                ));
+            }
+
+            limits
+                .insert_extern(namespace, path)
+                .map_err(|error| limit_error(&syntax, error, reader))?;
+        }
+
+        Ok(limits)
+    }
+}
+
+// Reports the error at the namespace segment or type name that's wrong rather than the whole path.
+fn limit_error(syntax: &syn::UseTree, error: LimitError, reader: &winmd::TypeReader) -> syn::Error {
+    let path: Vec<String> = match &error {
+        LimitError::Namespace {
+            namespace,
+            unknown_segment,
+            ..
+        } => namespace
+            .split('.')
+            .take(unknown_segment + 1)
+            .map(|segment| segment.to_string())
+            .collect(),
+        LimitError::Type {
+            namespace, name, ..
+        }
+        | LimitError::Unavailable {
+            namespace, name, ..
+        } => namespace
+            .split('.')
+            .map(namespace_literal_to_rough_namespace)
+            .chain(std::iter::once(name.clone()))
+            .collect(),
+        LimitError::Member {
+            namespace,
+            name,
+            member,
+            ..
+        } => namespace
+            .split('.')
+            .chain(name.split('`').take(1))
+            .map(namespace_literal_to_rough_namespace)
+            .chain(std::iter::once(member.clone()))
+            .collect(),
+    };
+
+    let span = find_span(syntax, &path).unwrap_or_else(|| syntax.span());
+
+    // An unknown namespace is often due to missing or misconfigured metadata so the error notes
+    // where the metadata was loaded from.
+    if let LimitError::Namespace { .. } = error {
+        syn::Error::new(span, format!("{} ({})", error, loaded_files(reader)))
+    } else {
+        syn::Error::new(span, error)
+    }
+}
+
+/// Describes the metadata files that were loaded by the number of files in each directory
+pub fn loaded_files(reader: &winmd::TypeReader) -> String {
+    let mut dirs = std::collections::BTreeMap::<_, usize>::new();
+
+    for file in &reader.files {
+        *dirs.entry(file.path.parent()).or_default() += 1;
+    }
+
+    if dirs.is_empty() {
+        return "no metadata files were loaded".to_string();
+    }
+
+    let dirs = dirs
+        .iter()
+        .map(|(dir, count)| match dir {
+            Some(dir) => format!("{} in {:?}", count, dir),
+            None => count.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("metadata files loaded: {}", dirs)
+}
+
+// Finds the identifier at the end of the path, comparing namespace segments as `build!` does.
+fn find_span(tree: &syn::UseTree, path: &[String]) -> Option<proc_macro2::Span> {
+    let (first, rest) = path.split_first()?;
+
+    match tree {
+        syn::UseTree::Path(p) => {
+            if namespace_literal_to_rough_namespace(&p.ident.to_string()) != *first {
+                None
+            } else if rest.is_empty() {
+                Some(p.ident.span())
+            } else {
+                find_span(&p.tree, rest)
+            }
+        }
+        syn::UseTree::Name(n) if rest.is_empty() && n.ident == first => Some(n.ident.span()),
+        syn::UseTree::Rename(r) if rest.is_empty() && r.ident == first => Some(r.ident.span()),
+        syn::UseTree::Group(g) => g.items.iter().find_map(|tree| find_span(tree, path)),
+        _ => None,
+    }
+}
+
+pub struct TypesDeclaration {
+    pub types: NamespaceTypes,
+    pub syntax: syn::UseTree,
+}
+
+impl std::cmp::PartialOrd for TypesDeclaration {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::cmp::Ord for TypesDeclaration {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.types.cmp(&other.types)
+    }
+}
+
+impl PartialEq for TypesDeclaration {
+    fn eq(&self, other: &Self) -> bool {
+        self.types == other.types
+    }
+}
+
+impl Eq for TypesDeclaration {}
+
+/// A type selected under another name such as `windows::ui::Colors as UiColors`
+pub struct RenameDeclaration {
+    pub rename: TypeRename,
+    pub syntax: syn::UseTree,
+}
+
+/// A type selected along with only the members that are used such as
+/// `windows::storage::StorageFile::{get_file_from_path_async, name}`
+pub struct MembersDeclaration {
+    pub members: TypeMembers,
+    pub syntax: syn::UseTree,
+}
+
+/// The types selected by a single use tree, which may select types from several namespaces with
+/// nested groups such as `windows::{ui::Colors, storage::*}`
+pub struct UseTreeDeclarations {
+    pub types: Vec<TypesDeclaration>,
+    pub renames: Vec<RenameDeclaration>,
+    pub members: Vec<MembersDeclaration>,
+}
+
+impl TryFrom<syn::UseTree> for UseTreeDeclarations {
+    type Error = syn::Error;
+    fn try_from(tree: syn::UseTree) -> Result<Self, Self::Error> {
+        let mut types = Vec::new();
+        let mut renames = Vec::new();
+        let mut members = Vec::new();
+        use_tree_to_namespace_types(&tree, &mut types, &mut renames, &mut members)?;
+
+        Ok(Self {
+            types: types
+                .into_iter()
+                .map(|types| TypesDeclaration {
+                    types,
+                    syntax: tree.clone(),
+                })
+                .collect(),
+            renames: renames
+                .into_iter()
+                .map(|rename| RenameDeclaration {
+                    rename,
+                    syntax: tree.clone(),
+                })
+                .collect(),
+            members: members
+                .into_iter()
+                .map(|members| MembersDeclaration {
+                    members,
+                    syntax: tree.clone(),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// A namespace provided by another crate's bindings such as `extern windows::ui from my_bindings`
+pub struct ExternDeclaration {
+    /// The namespace with segments separated by `.`
+    pub namespace: String,
+    /// The path to the module the other crate's bindings are included in
+    pub path: String,
+    pub syntax: syn::UseTree,
+}
+
+impl syn::parse::Parse for ExternDeclaration {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        input.parse::<syn::Token![extern]>()?;
+        let syntax: syn::UseTree = input.parse()?;
+
+        let mut segments = Vec::new();
+        let mut tree = &syntax;
+
+        loop {
+            match tree {
+                syn::UseTree::Path(p) => {
+                    segments.push(p.ident.to_string());
+                    tree = &p.tree;
+                }
+                syn::UseTree::Name(n) => {
+                    segments.push(n.ident.to_string());
+                    break;
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        tree,
+                        "Expected a namespace such as `windows::ui`",
+                    ))
+                }
+            }
+        }
+
+        let from: syn::Ident = input.parse()?;
+
+        if from != "from" {
+            return Err(syn::Error::new(
+                from.span(),
+                "Expected `from` followed by the path to another crate's bindings",
+            ));
+        }
+
+        let path: syn::Path = input.parse()?;
+
+        // The re-exports are generated in nested modules so paths relative to the current module
+        // would resolve differently in each of them.
+        if let Some(segment) = path
+            .segments
+            .iter()
+            .find(|segment| segment.ident == "self" || segment.ident == "super")
+        {
+            return Err(syn::Error::new_spanned(
+                segment,
+                "Expected a path starting with a crate name or `crate`",
+            ));
+        }
+
+        let path = path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+
+        Ok(Self {
+            namespace: namespace_literal_to_rough_namespace(&segments.join(".")),
+            path,
+            syntax,
+        })
+    }
+}
+
+/// How the DLLs in the workspace's `.windows` directory are deployed next to the build's binaries,
+/// such as `deploy { files = ["Component.dll"], symlink = true }`
+pub struct DeployDeclaration {
+    /// Whether the DLLs are deployed at all
+    pub enabled: bool,
+    /// The names of the files to deploy, or every file when empty
+    pub files: Vec<syn::LitStr>,
+    /// Whether the files are symlinked rather than copied
+    pub symlink: bool,
+    /// Whether each deployed file is reported as a cargo warning
+    pub report: bool,
+}
+
+impl Default for DeployDeclaration {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            files: Vec::new(),
+            symlink: false,
+            report: false,
+        }
+    }
+}
+
+impl syn::parse::Parse for DeployDeclaration {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut deploy = Self::default();
+
+        while !input.is_empty() {
+            let name: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+
+            if name == "files" {
+                let content;
+                syn::bracketed!(content in input);
+
+                deploy.files = content
+                    .parse_terminated::<syn::LitStr, syn::Token![,]>(|input| input.parse())?
+                    .into_iter()
+                    .collect();
+            } else {
+                let option = if name == "enabled" {
+                    &mut deploy.enabled
+                } else if name == "symlink" {
+                    &mut deploy.symlink
+                } else if name == "report" {
+                    &mut deploy.report
+                } else {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "Expected `enabled`, `files`, `symlink` or `report`",
+                    ));
+                };
+
+                *option = input.parse::<syn::LitBool>()?.value;
+            }
+
+            if input.is_empty() {
+                break;
+            }
+
+            input.parse::<syn::Token![,]>()?;
+        }
+
+        Ok(deploy)
+    }
+}
+
+/// A targeted contract version such as `UniversalApiContract = 10`
+pub struct ContractDeclaration {
+    /// The contract name with any namespace separated by `.`
+    pub contract: String,
+    /// The contract version with the major version in the high word
+    pub version: u32,
+    pub syntax: syn::Path,
+}
+
+impl syn::parse::Parse for ContractDeclaration {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let syntax: syn::Path = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let literal: syn::Lit = input.parse()?;
+
+        let mut segments: Vec<String> = syntax
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+
+        let name = segments.pop().unwrap();
+
+        let contract = if segments.is_empty() {
+            name
+        } else {
+            let namespace = namespace_literal_to_rough_namespace(&segments.join("."));
+            format!("{}.{}", namespace, name)
+        };
+
+        let version = match &literal {
+            syn::Lit::Int(major) => major.base10_parse::<u16>().ok().map(|major| (major, 0)),
+            syn::Lit::Float(version) => {
+                let mut parts = version.base10_digits().splitn(2, '.');
+
+                match (
+                    parts.next().and_then(|major| major.parse::<u16>().ok()),
+                    parts.next().and_then(|minor| minor.parse::<u16>().ok()),
+                ) {
+                    (Some(major), Some(minor)) => Some((major, minor)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let (major, minor) = version.ok_or_else(|| {
+            syn::Error::new(
+                literal.span(),
+                "Expected a contract version such as `10` or `10.0`",
+            )
+        })?;
+
+        Ok(Self {
+            contract,
+            version: (major as u32) << 16 | minor as u32,
+            syntax,
+        })
+    }
+}
+
+impl syn::parse::Parse for BuildLimits {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut types = std::collections::BTreeSet::new();
+        let mut exclusions = std::collections::BTreeSet::new();
+        let mut renames = Vec::new();
+        let mut members = Vec::new();
+        let mut contracts = Vec::new();
+        let mut metadata = Vec::new();
+        let mut externs = Vec::new();
+        let mut deploy = None;
+        loop {
+            if input.is_empty() {
+                break;
+            }
+
+            if input.peek(syn::Token![extern]) {
+                externs.push(input.parse()?);
+                continue;
+            }
+
+            // A `contracts { .. }`, `metadata { .. }` or `deploy { .. }` block can't be confused with a use tree
+            // since a group must follow a path separator.
+            if input.peek(syn::Ident) && input.peek2(syn::token::Brace) {
+                let keyword: syn::Ident = input.parse()?;
+                let content;
+                syn::braced!(content in input);
+
+                if keyword == "contracts" {
+                    let declarations = content
+                        .parse_terminated::<ContractDeclaration, syn::Token![,]>(
+                            ContractDeclaration::parse,
+                        )?;
+
+                    contracts.extend(declarations);
+                } else if keyword == "metadata" {
+                    let paths = content
+                        .parse_terminated::<syn::LitStr, syn::Token![,]>(|input| input.parse())?;
+
+                    metadata.extend(paths);
+                } else if keyword == "deploy" {
+                    if deploy.is_some() {
+                        return Err(syn::Error::new(
+                            keyword.span(),
+                            "Only one `deploy` block is allowed",
+                        ));
+                    }
+
+                    deploy = Some(content.parse()?);
+                } else {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "Expected `contracts`, `metadata`, `deploy` or a type path",
+                    ));
+                }
+
+                continue;
+            }
+
+            // Types are excluded with a `!` in front of their path, such as
+            // `!windows::ui::xaml::media::media3d::*`.
+            let exclude = input.parse::<Option<syn::Token![!]>>()?.is_some();
+
+            let use_tree: syn::UseTree = input.parse()?;
+            let declarations: UseTreeDeclarations = use_tree.try_into()?;
+
+            if exclude {
+                if let Some(rename) = declarations.renames.first() {
+                    return Err(syn::Error::new_spanned(
+                        &rename.syntax,
+                        "Excluded types can't be renamed",
+                    ));
+                }
+
+                if let Some(members) = declarations.members.first() {
+                    return Err(syn::Error::new_spanned(
+                        &members.syntax,
+                        "Excluded types can't be limited to members",
+                    ));
+                }
+
+                exclusions.extend(declarations.types);
+            } else {
+                types.extend(declarations.types);
+                renames.extend(declarations.renames);
+                members.extend(declarations.members);
+            }
+        }
+        Ok(Self {
+            types,
+            exclusions,
+            renames,
+            members,
+            contracts,
+            externs,
+            metadata,
+            deploy: deploy.unwrap_or_default(),
+        })
+    }
+}
+
+fn use_tree_to_namespace_types(
+    use_tree: &syn::UseTree,
+    types: &mut Vec<NamespaceTypes>,
+    renames: &mut Vec<TypeRename>,
+    members: &mut Vec<TypeMembers>,
+) -> syn::parse::Result<()> {
+    fn check_for_module_instead_of_type(
+        name: &str,
+        span: proc_macro2::Span,
+    ) -> syn::parse::Result<()> {
+        let error = Err(syn::Error::new(
+            span,
+            "Expected `*` or type name, but found what appears to be a module",
+        ));
+        if name.to_lowercase() == name {
+            return error;
+        }
+        Ok(())
+    }
+
+    // The members of a type are written in snake case after the type's name, as in
+    // `StorageFile::name` or `StorageFile::{name, path}`.
+    fn member_names(tree: &syn::UseTree) -> Option<Vec<String>> {
+        let is_member = |name: &syn::Ident| {
+            let name = name.to_string();
+            name.to_lowercase() == name
+        };
+
+        match tree {
+            syn::UseTree::Name(n) if is_member(&n.ident) => Some(vec![n.ident.to_string()]),
+            syn::UseTree::Group(g) if !g.items.is_empty() => g
+                .items
+                .iter()
+                .map(|tree| match tree {
+                    syn::UseTree::Name(n) if is_member(&n.ident) => Some(n.ident.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    fn recurse(
+        tree: &syn::UseTree,
+        current: &str,
+        types: &mut Vec<NamespaceTypes>,
+        renames: &mut Vec<TypeRename>,
+        members: &mut Vec<TypeMembers>,
+    ) -> syn::parse::Result<()> {
+        match tree {
+            syn::UseTree::Path(p) => {
+                let name = p.ident.to_string();
+
+                if name.to_lowercase() != name {
+                    if let Some(names) = member_names(&p.tree) {
+                        members.push(TypeMembers {
+                            namespace: namespace_literal_to_rough_namespace(current),
+                            name,
+                            members: names,
+                        });
+                        return Ok(());
+                    }
+                }
+
+                let current = if current.is_empty() {
+                    p.ident.to_string()
+                } else {
+                    format!("{}.{}", current, p.ident)
+                };
+
+                recurse(&p.tree, &current, types, renames, members)
+            }
+            syn::UseTree::Glob(_) => {
+                types.push(NamespaceTypes {
+                    namespace: namespace_literal_to_rough_namespace(current),
+                    limit: TypeLimit::All,
+                });
+                Ok(())
+            }
+            syn::UseTree::Group(g) => {
+                // The names in a group are kept together while any nested paths, groups or
+                // renames are treated as though they had been written out in full.
+                let mut names = Vec::new();
+                for tree in &g.items {
+                    match tree {
+                        syn::UseTree::Name(n) => {
+                            let name = n.ident.to_string();
+                            check_for_module_instead_of_type(&name, n.span())?;
+                            names.push(name);
+                        }
+                        _ => recurse(tree, current, types, renames, members)?,
+                    }
+                }
+
+                if !names.is_empty() {
+                    types.push(NamespaceTypes {
+                        namespace: namespace_literal_to_rough_namespace(current),
+                        limit: TypeLimit::Some(names),
+                    });
+                }
+                Ok(())
+            }
+            syn::UseTree::Name(n) => {
+                let name = n.ident.to_string();
+                check_for_module_instead_of_type(&name, n.span())?;
+                types.push(NamespaceTypes {
+                    namespace: namespace_literal_to_rough_namespace(current),
+                    limit: TypeLimit::Some(vec![name]),
+                });
+                Ok(())
+            }
+            syn::UseTree::Rename(r) => {
+                let name = r.ident.to_string();
+                check_for_module_instead_of_type(&name, r.ident.span())?;
+
+                if r.rename == "_" {
+                    return Err(syn::Error::new(
+                        r.rename.span(),
+                        "Expected a name to rename the type to",
+                    ));
+                }
+
+                renames.push(TypeRename {
+                    namespace: namespace_literal_to_rough_namespace(current),
+                    name,
+                    rename: r.rename.to_string(),
+                });
+                Ok(())
+            }
+        }
+    }
+
+    recurse(use_tree, "", types, renames, members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let build: BuildLimits = syn::parse_str(
+            "contracts { UniversalApiContract = 10.1 }
+            metadata { \"winmd\", \"../component/Component.winmd\" }
+            windows::{ui::{Colors as UiColors, text::*}, storage::Buffer}
+            !windows::ui::text::FontWeights",
+        )
+        .unwrap();
+
+        let types: Vec<&NamespaceTypes> = build.types.iter().map(|t| &t.types).collect();
+        assert_eq!(
+            types,
+            [
+                &NamespaceTypes {
+                    namespace: "windows.storage".to_owned(),
+                    limit: TypeLimit::Some(vec!["Buffer".to_owned()]),
+                },
+                &NamespaceTypes {
+                    namespace: "windows.ui.text".to_owned(),
+                    limit: TypeLimit::All,
+                },
+            ]
+        );
+
+        assert_eq!(build.renames.len(), 1);
+        assert_eq!(
+            build.renames[0].rename,
+            TypeRename {
+                namespace: "windows.ui".to_owned(),
+                name: "Colors".to_owned(),
+                rename: "UiColors".to_owned(),
+            }
+        );
+
+        assert_eq!(build.exclusions.len(), 1);
+        assert_eq!(build.contracts[0].contract, "UniversalApiContract");
+        assert_eq!(build.contracts[0].version, 10 << 16 | 1);
+
+        let metadata: Vec<String> = build.metadata.iter().map(|path| path.value()).collect();
+        assert_eq!(metadata, ["winmd", "../component/Component.winmd"]);
+
+        assert!(build.deploy.enabled);
+        assert!(build.deploy.files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_members() {
+        let build: BuildLimits = syn::parse_str(
+            "windows::storage::StorageFile::{get_file_from_path_async, name}
+            windows::{Storage::FileIO::read_text_async, ui::Colors}",
+        )
+        .unwrap();
+
+        let members: Vec<&TypeMembers> = build.members.iter().map(|m| &m.members).collect();
+        assert_eq!(
+            members,
+            [
+                &TypeMembers {
+                    namespace: "windows.storage".to_owned(),
+                    name: "StorageFile".to_owned(),
+                    members: vec!["get_file_from_path_async".to_owned(), "name".to_owned()],
+                },
+                &TypeMembers {
+                    namespace: "windows.storage".to_owned(),
+                    name: "FileIO".to_owned(),
+                    members: vec!["read_text_async".to_owned()],
+                },
+            ]
+        );
+
+        assert_eq!(build.types.len(), 1);
+        assert!(syn::parse_str::<BuildLimits>("!windows::storage::StorageFile::name").is_err());
+    }
+
+    #[test]
+    fn test_parse_extern() {
+        let build: BuildLimits = syn::parse_str(
+            "extern windows::ui from my_bindings
+            extern windows::Storage from ::other::bindings
+            windows::ui::xaml::*",
+        )
+        .unwrap();
+
+        let externs: Vec<(&str, &str)> = build
+            .externs
+            .iter()
+            .map(|e| (e.namespace.as_str(), e.path.as_str()))
+            .collect();
+
+        assert_eq!(
+            externs,
+            [
+                ("windows.ui", "my_bindings"),
+                ("windows.storage", "other::bindings")
+            ]
+        );
+
+        assert_eq!(build.types.len(), 1);
+        assert!(syn::parse_str::<BuildLimits>("extern windows::ui::* from my_bindings").is_err());
+        assert!(syn::parse_str::<BuildLimits>("extern windows::ui in my_bindings").is_err());
+        assert!(syn::parse_str::<BuildLimits>("extern windows::ui from self::bindings").is_err());
+        assert!(syn::parse_str::<BuildLimits>("extern windows::ui from super::bindings").is_err());
+
+        let build: BuildLimits = syn::parse_str("extern windows::ui from crate::bindings").unwrap();
+        assert_eq!(build.externs[0].path, "crate::bindings");
+    }
+
+    #[test]
+    fn test_parse_deploy() {
+        let build: BuildLimits = syn::parse_str(
+            "deploy { files = [\"Component.dll\"], symlink = true, report = true }
+            windows::ui::Colors",
+        )
+        .unwrap();
+
+        let files: Vec<String> = build.deploy.files.iter().map(|file| file.value()).collect();
+        assert_eq!(files, ["Component.dll"]);
+        assert!(build.deploy.enabled && build.deploy.symlink && build.deploy.report);
+
+        let build: BuildLimits = syn::parse_str("deploy { enabled = false }").unwrap();
+        assert!(!build.deploy.enabled);
+
+        assert!(syn::parse_str::<BuildLimits>("deploy { copy = true }").is_err());
+        assert!(syn::parse_str::<BuildLimits>("deploy {} deploy {}").is_err());
+    }
+}
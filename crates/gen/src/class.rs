@@ -120,6 +120,8 @@ impl Class {
         let type_name = self.type_name(&name);
         let methods = gen_method(&self.interfaces);
         let call_factory = self.gen_call_factory();
+        let attributes = gen_type_attributes(&self.name.def);
+        let overloads = gen_overloads(&self.name, &self.interfaces);
 
         if let Some(default_interface) = self
             .interfaces
@@ -160,6 +162,7 @@ impl Class {
             };
 
             quote! {
+                #attributes
                 #[repr(transparent)]
                 pub struct #name(::winrt::Object);
                 impl #name {
@@ -218,15 +221,18 @@ impl Class {
                 #iterator
                 #send_sync
                 #future
+                #overloads
             }
         } else {
             quote! {
+                #attributes
                 pub struct #name {}
                 impl #name {
                     #methods
                     #call_factory
                 }
                 #type_name
+                #overloads
             }
         }
     }
@@ -270,7 +276,7 @@ impl Class {
         for interface in &self.interfaces {
             if (interface.kind != InterfaceKind::Statics
                 && interface.kind != InterfaceKind::Composable)
-                || interface.methods.is_empty()
+                || !interface.methods.iter().any(|method| method.is_available)
             {
                 continue;
             }
@@ -0,0 +1,235 @@
+use crate::*;
+use squote::{quote, TokenStream};
+
+// Provides idiomatic helpers for the well-known WinRT collection interfaces on top of the raw
+// methods generated from metadata, which are all left as they are. Lookups that fail because the
+// index or key is out of bounds return `None` rather than an error so that they read like their
+// counterparts on Rust's own collections.
+pub fn gen_collections(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Foundation.Collections" {
+        return TokenStream::new();
+    }
+
+    match name.name {
+        "IVectorView`1" => gen_vector_view(&quote! { IVectorView }),
+        "IVector`1" => gen_vector(&quote! { IVector }),
+        "IObservableVector`1" => gen_vector(&quote! { IObservableVector }),
+        "IMapView`2" => gen_map_view(&quote! { IMapView }),
+        "IMap`2" => gen_map(&quote! { IMap }),
+        "IObservableMap`2" => gen_map(&quote! { IObservableMap }),
+        _ => TokenStream::new(),
+    }
+}
+
+fn gen_vector_view(interface: &TokenStream) -> TokenStream {
+    quote! {
+        impl<T: ::winrt::RuntimeType> #interface<T> {
+            pub fn len(&self) -> ::winrt::Result<usize> {
+                ::std::result::Result::Ok(self.size()? as usize)
+            }
+
+            pub fn is_empty(&self) -> ::winrt::Result<bool> {
+                ::std::result::Result::Ok(self.size()? == 0)
+            }
+
+            pub fn get(&self, index: usize) -> ::winrt::Result<::std::option::Option<T>> {
+                if index > u32::MAX as usize {
+                    return ::std::result::Result::Ok(::std::option::Option::None);
+                }
+
+                match self.get_at(index as u32) {
+                    ::std::result::Result::Ok(value) => ::std::result::Result::Ok(::std::option::Option::Some(value)),
+                    ::std::result::Result::Err(error) if error.code() == ::winrt::ErrorCode::E_BOUNDS => ::std::result::Result::Ok(::std::option::Option::None),
+                    ::std::result::Result::Err(error) => ::std::result::Result::Err(error),
+                }
+            }
+        }
+    }
+}
+
+fn gen_vector(interface: &TokenStream) -> TokenStream {
+    let view = gen_vector_view(interface);
+
+    quote! {
+        #view
+
+        impl<T: ::winrt::RuntimeType> #interface<T> {
+            pub fn push<'a, T0__: ::std::convert::Into<::winrt::Param<'a, T>>>(&self, value: T0__) -> ::winrt::Result<()> {
+                self.append(value)
+            }
+
+            // The items are retrieved with a single GetMany call and those to be removed are then
+            // removed from the back so that the remaining indices stay valid.
+            pub fn retain<F: ::std::ops::FnMut(&T) -> bool>(&self, mut f: F) -> ::winrt::Result<()> {
+                let size = self.size()?;
+                let items = ::winrt::get_chunk::<T, _>(size, |items| self.get_many(0, items))?;
+
+                let removed: ::std::vec::Vec<u32> = items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| !f(item))
+                    .map(|(index, _)| index as u32)
+                    .collect();
+
+                for index in removed.into_iter().rev() {
+                    self.remove_at(index)?;
+                }
+
+                ::std::result::Result::Ok(())
+            }
+
+            /// Appends the values, stopping at the first that fails to be appended.
+            pub fn try_extend<I: ::std::iter::IntoIterator<Item = T>>(&self, iter: I) -> ::winrt::Result<()> {
+                for value in iter {
+                    self.append(value)?;
+                }
+
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        /// Appends the values with `try_extend`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if a value fails to be appended.
+        impl<T: ::winrt::RuntimeType> ::std::iter::Extend<T> for #interface<T> {
+            fn extend<I: ::std::iter::IntoIterator<Item = T>>(&mut self, iter: I) {
+                self.try_extend(iter).expect("Failed to append a value to the vector");
+            }
+        }
+    }
+}
+
+fn gen_map_view(interface: &TokenStream) -> TokenStream {
+    quote! {
+        impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> #interface<K, V> {
+            pub fn len(&self) -> ::winrt::Result<usize> {
+                ::std::result::Result::Ok(self.size()? as usize)
+            }
+
+            pub fn is_empty(&self) -> ::winrt::Result<bool> {
+                ::std::result::Result::Ok(self.size()? == 0)
+            }
+
+            pub fn get<'a, T0__: ::std::convert::Into<::winrt::Param<'a, K>>>(&self, key: T0__) -> ::winrt::Result<::std::option::Option<V>> {
+                match self.lookup(key) {
+                    ::std::result::Result::Ok(value) => ::std::result::Result::Ok(::std::option::Option::Some(value)),
+                    ::std::result::Result::Err(error) if error.code() == ::winrt::ErrorCode::E_BOUNDS => ::std::result::Result::Ok(::std::option::Option::None),
+                    ::std::result::Result::Err(error) => ::std::result::Result::Err(error),
+                }
+            }
+
+            pub fn contains_key<'a, T0__: ::std::convert::Into<::winrt::Param<'a, K>>>(&self, key: T0__) -> ::winrt::Result<bool> {
+                self.has_key(key)
+            }
+        }
+    }
+}
+
+fn gen_map(interface: &TokenStream) -> TokenStream {
+    let view = gen_map_view(interface);
+
+    quote! {
+        #view
+
+        impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> #interface<K, V> {
+            // The keys to be removed are all collected before any are removed since removing them
+            // while iterating would invalidate the iterator.
+            pub fn retain<F: ::std::ops::FnMut(&K, &V) -> bool>(&self, mut f: F) -> ::winrt::Result<()> {
+                let iterator = self.first()?;
+                let mut removed = ::std::vec::Vec::new();
+
+                loop {
+                    let pairs = ::winrt::get_chunk::<IKeyValuePair<K, V>, _>(::winrt::DEFAULT_CHUNK_SIZE, |items| iterator.get_many(items))?;
+
+                    if pairs.is_empty() {
+                        break;
+                    }
+
+                    for pair in pairs {
+                        let key = pair.key()?;
+
+                        if !f(&key, &pair.value()?) {
+                            removed.push(key);
+                        }
+                    }
+                }
+
+                for key in &removed {
+                    self.remove(key)?;
+                }
+
+                ::std::result::Result::Ok(())
+            }
+
+            /// Inserts the pairs, stopping at the first that fails to be inserted.
+            pub fn try_extend<I: ::std::iter::IntoIterator<Item = (K, V)>>(&self, iter: I) -> ::winrt::Result<()> {
+                for (key, value) in iter {
+                    self.insert(key, value)?;
+                }
+
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        /// Inserts the pairs with `try_extend`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if a pair fails to be inserted.
+        impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> ::std::iter::Extend<(K, V)> for #interface<K, V> {
+            fn extend<I: ::std::iter::IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+                self.try_extend(iter).expect("Failed to insert a pair into the map");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn interface(namespace: &str, name: &str) -> Interface {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def((namespace, name));
+
+        match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Interface(t) => t,
+            _ => panic!("TypeDefinition not an interface"),
+        }
+    }
+
+    #[test]
+    fn test_vector_collections() {
+        let t = interface("Windows.Foundation.Collections", "IVectorView`1");
+        let collections = gen_collections(&t.name).into_string();
+        assert!(collections.contains("fn len"));
+        assert!(collections.contains("fn get"));
+        assert!(!collections.contains("fn push"));
+
+        let t = interface("Windows.Foundation.Collections", "IVector`1");
+        let collections = gen_collections(&t.name).into_string();
+        assert!(collections.contains("fn push"));
+        assert!(collections.contains("fn retain"));
+        assert!(collections.contains("fn try_extend"));
+        assert!(collections.contains("Extend"));
+    }
+
+    #[test]
+    fn test_map_collections() {
+        let t = interface("Windows.Foundation.Collections", "IMapView`2");
+        let collections = gen_collections(&t.name).into_string();
+        assert!(collections.contains("fn contains_key"));
+        assert!(!collections.contains("fn retain"));
+
+        let t = interface("Windows.Foundation.Collections", "IMap`2");
+        let collections = gen_collections(&t.name).into_string();
+        assert!(collections.contains("fn retain"));
+        assert!(collections.contains("fn try_extend"));
+        assert!(collections.contains("Extend"));
+
+        let t = interface("Windows.Foundation.Collections", "IIterable`1");
+        assert!(gen_collections(&t.name).into_string().is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use squote::{quote, TokenStream};
+use std::collections::BTreeMap;
+
+/// The API contract and version, or the Windows version, that a type was introduced in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Introduced {
+    /// The full name of the contract or `None` for types versioned by Windows release
+    pub contract: Option<String>,
+    /// The contract version with the major version in the high word or the Windows version
+    pub version: u32,
+}
+
+impl Introduced {
+    pub fn from_attributes<I: IntoIterator<Item = winmd::Attribute>>(
+        attributes: I,
+    ) -> Option<Self> {
+        for attribute in attributes {
+            match attribute.name() {
+                ("Windows.Foundation.Metadata", "ContractVersionAttribute") => {
+                    let args = attribute.args();
+
+                    // Contract types themselves only carry a version.
+                    if args.len() < 2 {
+                        continue;
+                    }
+
+                    if let (Some(contract), winmd::AttributeArg::U32(version)) =
+                        (contract_name(&args[0].1), &args[1].1)
+                    {
+                        return Some(Self {
+                            contract: Some(contract),
+                            version: *version,
+                        });
+                    }
+                }
+                ("Windows.Foundation.Metadata", "VersionAttribute") => {
+                    if let Some((_, winmd::AttributeArg::U32(version))) = attribute.args().first() {
+                        return Some(Self {
+                            contract: None,
+                            version: *version,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// Returns `true` if the API is available to a build targeting the given contract versions. APIs
+    /// from contracts that aren't targeted are always available.
+    pub fn is_available(&self, contracts: &BTreeMap<String, u32>) -> bool {
+        match &self.contract {
+            Some(contract) => contracts
+                .get(contract)
+                .iter()
+                .all(|target| self.version <= **target),
+            None => true,
+        }
+    }
+
+    pub fn gen_doc(&self) -> TokenStream {
+        let doc = escape(&format!(" Introduced in {}.", self));
+        quote! { #[doc = #doc] }
+    }
+}
+
+impl std::fmt::Display for Introduced {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.contract {
+            Some(contract) => write!(
+                f,
+                "`{}` version {}",
+                contract,
+                format_contract_version(self.version)
+            ),
+            None => write!(f, "version 0x{:08X}", self.version),
+        }
+    }
+}
+
+/// The deprecation or removal of a type or member
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deprecated {
+    pub message: String,
+    /// `true` if the API has been removed rather than merely deprecated
+    pub removed: bool,
+    pub contract: Option<String>,
+    pub version: u32,
+}
+
+impl Deprecated {
+    pub fn from_attributes<I: IntoIterator<Item = winmd::Attribute>>(
+        attributes: I,
+    ) -> Option<Self> {
+        for attribute in attributes {
+            if attribute.name() != ("Windows.Foundation.Metadata", "DeprecatedAttribute") {
+                continue;
+            }
+
+            let args = attribute.args();
+
+            // The arguments are the message, the DeprecationType enum (where the Remove variant has
+            // a value of 1), the version and optionally either the contract name or a Platform.
+            if let (
+                Some((_, winmd::AttributeArg::String(message))),
+                Some((_, winmd::AttributeArg::I32(kind))),
+                Some((_, winmd::AttributeArg::U32(version))),
+            ) = (args.first(), args.get(1), args.get(2))
+            {
+                let contract = match args.get(3) {
+                    Some((_, winmd::AttributeArg::String(contract))) => Some(contract.clone()),
+                    _ => None,
+                };
+
+                return Some(Self {
+                    message: message.clone(),
+                    removed: *kind == 1,
+                    contract,
+                    version: *version,
+                });
+            }
+        }
+
+        None
+    }
+
+    pub fn gen(&self) -> TokenStream {
+        let note = if self.removed {
+            escape(&format!("Removed: {}", self.message))
+        } else {
+            escape(&self.message)
+        };
+
+        match &self.contract {
+            Some(contract) => {
+                let since = escape(&format!(
+                    "{} {}",
+                    contract,
+                    format_contract_version(self.version)
+                ));
+
+                quote! { #[deprecated(since = #since, note = #note)] }
+            }
+            None => quote! { #[deprecated(note = #note)] },
+        }
+    }
+}
+
+/// Generates the documentation and deprecation attributes for a type definition
+pub fn gen_type_attributes(def: &winmd::TypeDef) -> TokenStream {
+    let mut tokens = TokenStream::new();
+
+    if let Some(introduced) = Introduced::from_attributes(def.attributes()) {
+        tokens.combine(&introduced.gen_doc());
+    }
+
+    if let Some(deprecated) = Deprecated::from_attributes(def.attributes()) {
+        tokens.combine(&deprecated.gen());
+    }
+
+    tokens
+}
+
+fn contract_name(arg: &winmd::AttributeArg) -> Option<String> {
+    match arg {
+        winmd::AttributeArg::TypeDef(def) => {
+            let (namespace, name) = def.name();
+            Some(format!("{}.{}", namespace, name))
+        }
+        winmd::AttributeArg::String(name) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+// Metadata strings are emitted as string literals so any quotes or backslashes must be escaped.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn format_contract_version(version: u32) -> String {
+    format!("{}.{}", version >> 16, version & 0xFFFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_introduced() {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.Foundation", "IStringable"));
+        let introduced = Introduced::from_attributes(def.attributes()).unwrap();
+
+        assert!(introduced.contract.as_deref() == Some("Windows.Foundation.FoundationContract"));
+        assert!(introduced.version == 0x10000);
+        assert!(gen_type_attributes(&def)
+            .into_string()
+            .contains("Introduced in `Windows.Foundation.FoundationContract` version 1.0."));
+    }
+
+    #[test]
+    fn test_deprecated() {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.ApplicationModel.Search", "ISearchPane"));
+        let deprecated = Deprecated::from_attributes(def.attributes()).unwrap();
+
+        assert!(!deprecated.removed);
+        assert!(
+            deprecated.contract.as_deref()
+                == Some("Windows.ApplicationModel.Search.SearchContract")
+        );
+
+        let tokens = gen_type_attributes(&def).into_string();
+        assert!(tokens.contains("deprecated"));
+        assert!(tokens.contains("may be altered or unavailable"));
+
+        let def = reader.resolve_type_def(("Windows.Foundation", "IStringable"));
+        assert!(Deprecated::from_attributes(def.attributes()).is_none());
+    }
+
+    #[test]
+    fn test_limit_contracts() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+
+        limits
+            .insert_contract("DevicesLowLevelContract", 0x10000)
+            .unwrap();
+
+        assert!(
+            limits
+                .contracts
+                .get("Windows.Devices.DevicesLowLevelContract")
+                == Some(&0x10000)
+        );
+        assert!(limits
+            .insert_contract("windows.devices.DevicesLowLevelContract", 0x10000)
+            .is_ok());
+        assert!(limits.insert_contract("AdcController", 0x10000).is_err());
+
+        let def = reader.resolve_type_def(("Windows.Devices.Adc", "AdcController"));
+        let mut t = TypeDefinition::from_type_def(&def);
+        t.limit_contracts(&limits.contracts);
+
+        let t = match t {
+            TypeDefinition::Class(t) => t,
+            _ => panic!("TypeDefinition not a class"),
+        };
+
+        let available = |interface: &str| {
+            t.interfaces
+                .iter()
+                .find(|i| i.name.name == interface)
+                .unwrap()
+                .methods
+                .iter()
+                .all(|method| method.is_available)
+        };
+
+        assert!(available("IAdcControllerStatics"));
+        assert!(!available("IAdcControllerStatics2"));
+
+        let tokens = t.gen().into_string();
+        assert!(tokens.contains("get_controllers_async"));
+        assert!(!tokens.contains("get_default_async"));
+
+        // The types that only unavailable methods depend on aren't needed.
+        let def = reader.resolve_type_def(("Windows.Devices.Adc", "IAdcControllerStatics2"));
+        let controller = reader.resolve_type_def(("Windows.Devices.Adc", "AdcController"));
+        let mut t = TypeDefinition::from_type_def(&def);
+        assert!(t.dependencies().contains(&controller));
+        t.limit_contracts(&limits.contracts);
+        assert!(t.dependencies() == [def]);
+
+        // Types selected by name must be available.
+        let error = limits
+            .insert(NamespaceTypes {
+                namespace: "windows.devices".to_owned(),
+                limit: TypeLimit::Some(vec!["LowLevelDevicesController".to_owned()]),
+            })
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "'LowLevelDevicesController' in `windows::devices` is not available in the targeted \
+             contract versions since it was introduced in \
+             `Windows.Devices.DevicesLowLevelContract` version 2.0"
+        );
+    }
+}
@@ -10,12 +10,19 @@ pub struct Delegate {
 
 impl Delegate {
     pub fn from_type_name(name: TypeName) -> Self {
+        let calling_namespace = name.namespace;
+        Self::from_type_name_in(name, calling_namespace)
+    }
+
+    /// Creates the delegate with its `Invoke` method's types named relative to the given
+    /// namespace, such as for a closure taken by a method of another namespace
+    pub fn from_type_name_in(name: TypeName, calling_namespace: &'static str) -> Self {
         let method = name
             .def
             .methods()
             .find(|method| method.name() == "Invoke")
             .unwrap();
-        let method = Method::from_method_def(&method, 3, &name.generics, &name.namespace);
+        let method = Method::from_method_def(&method, 3, &name.generics, calling_namespace);
         let guid = TypeGuid::from_type_def(&name.def);
         Self { name, method, guid }
     }
@@ -47,8 +54,10 @@ impl Delegate {
         };
 
         let invoke_upcall = self.method.gen_upcall(quote! { ((*this).invoke) }, true);
+        let attributes = gen_type_attributes(&self.name.def);
 
         quote! {
+            #attributes
             #[repr(transparent)]
             pub struct #definition(::winrt::IUnknown, #phantoms) where #constraints;
             impl<#constraints> ::std::clone::Clone for #name {
@@ -151,7 +160,7 @@ impl Delegate {
         }
     }
 
-    fn gen_fn_constraint(&self) -> TokenStream {
+    pub fn gen_fn_constraint(&self) -> TokenStream {
         let params = self.method.params.iter().map(|param| param.gen_fn());
 
         let return_type = if let Some(return_type) = &self.method.return_type {
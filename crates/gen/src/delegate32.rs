@@ -1,5 +1,5 @@
 use crate::*;
-use squote::{quote, TokenStream};
+use squote::{format_ident, quote, TokenStream};
 
 #[derive(Debug)]
 pub struct Delegate32 {
@@ -23,6 +23,71 @@ impl Delegate32 {
         self.method.dependencies()
     }
 
+    /// Returns the position of the parameter used to pass caller-supplied data through to the
+    /// callback, if the callback has one. Win32 metadata doesn't mark these explicitly so this
+    /// relies on the naming conventions used by the Windows SDK headers:
+    ///
+    /// * The last pointer-sized parameter named like a context, such as `context`, `pv_context`
+    ///   or `user_data`, is the context.
+    /// * Otherwise an `l_param` is the context unless the callback also takes a `w_param`, as a
+    ///   window procedure does, since the pair is then part of a message.
+    /// * Otherwise an enumeration callback whose parameters are unnamed in metadata passes the
+    ///   context as its last parameter if that parameter is pointer-sized.
+    ///
+    /// Any other callback has no context parameter and its closure is only available through
+    /// thread-local storage. The generated `with_context` names the parameter that was chosen.
+    pub fn context_param(&self) -> Option<usize> {
+        const CONTEXT_NAMES: &[&str] = &[
+            "context",
+            "user_context",
+            "p_context",
+            "pv_context",
+            "lp_context",
+            "dw_context",
+            "pv_callback_context",
+            "callback_state",
+            "caller_data",
+            "pv_arg",
+            "lp_parameter",
+            "dw_ref_data",
+            "user_data",
+        ];
+
+        let params = &self.method.params;
+
+        if params.iter().any(|param| param.array) {
+            return None;
+        }
+
+        let pointer_sized = |param: &Param| matches!(param.kind, TypeKind::ISize | TypeKind::USize);
+
+        let has_w_param = params.iter().any(|param| param.name == "w_param");
+
+        let by_name = params.iter().rposition(|param| {
+            pointer_sized(param)
+                && (CONTEXT_NAMES.contains(&param.name.as_str())
+                    || (param.name == "l_param" && !has_w_param))
+        });
+
+        if by_name.is_some() {
+            return by_name;
+        }
+
+        // Some callbacks like WNDENUMPROC have unnamed parameters in metadata but enumeration
+        // callbacks consistently pass the caller's LPARAM as their last parameter.
+        let unnamed = params.iter().all(|param| param.name.starts_with("param"));
+
+        if unnamed && self.name.name.contains("ENUM") {
+            if let Some(last) = params.last() {
+                if pointer_sized(last) {
+                    return Some(params.len() - 1);
+                }
+            }
+        }
+
+        None
+    }
+
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
 
@@ -40,9 +105,161 @@ impl Delegate32 {
             TokenStream::new()
         };
 
+        let closure = if self.method.params.iter().any(|param| param.array) {
+            TokenStream::new()
+        } else {
+            self.gen_closure()
+        };
+
         quote! {
             #[allow(non_camel_case_types)]
             pub type #name = extern "system" fn(#(#params),*) #return_type;
+            #closure
+        }
+    }
+
+    // Generates a companion `<NAME>_closure` type providing trampolines so that a Rust closure may
+    // be passed where the Win32 API expects a function pointer. A closure may always be stashed in
+    // thread-local storage and, if the callback has a context parameter, it may instead be passed
+    // directly through that parameter.
+    fn gen_closure(&self) -> TokenStream {
+        let name = self.name.gen();
+        let closure_name = format_ident!("{}_closure", self.name.name);
+
+        let names: Vec<_> = self
+            .method
+            .params
+            .iter()
+            .map(|param| format_ident(&param.name))
+            .collect();
+
+        let types: Vec<_> = self
+            .method
+            .params
+            .iter()
+            .map(|param| param.kind.gen_abi())
+            .collect();
+
+        let return_type = if let Some(return_type) = &self.method.return_type {
+            return_type.kind.gen_abi()
+        } else {
+            quote! { () }
+        };
+
+        let context = if let Some(position) = self.context_param() {
+            let context_type = &types[position];
+
+            let closure_names = names
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != position)
+                .map(|(_, name)| name);
+
+            let closure_types = types
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != position)
+                .map(|(_, kind)| kind);
+
+            let closure_types2 = closure_types.clone();
+            let context_name = &names[position];
+
+            let doc = format!(
+                " The context is passed to the callback as its `{}` parameter, as inferred from the naming conventions of the Windows SDK.",
+                self.method.params[position].name
+            );
+
+            quote! {
+                /// Returns a callback and the value to pass as its context parameter so that `callback`
+                /// is invoked directly without any thread-local state.
+                ///
+                #[doc = #doc]
+                ///
+                /// # Safety
+                ///
+                /// The returned context must only be passed to the API alongside the returned callback
+                /// and `callback` must outlive any use of them.
+                pub unsafe fn with_context<F: FnMut(#(#closure_types),*) -> #return_type>(callback: &mut F) -> (#name, #context_type) {
+                    (Self::context_trampoline::<F>, callback as *mut F as #context_type)
+                }
+                extern "system" fn context_trampoline<F: FnMut(#(#closure_types2),*) -> #return_type>(#(#names: #types),*) -> #return_type {
+                    ::winrt::abort_on_unwind(|| {
+                        let callback = unsafe { &mut *(#context_name as *mut F) };
+                        callback(#(#closure_names),*)
+                    })
+                }
+            }
+        } else {
+            TokenStream::new()
+        };
+
+        quote! {
+            #[allow(non_camel_case_types)]
+            pub struct #closure_name;
+            impl #closure_name {
+                /// Stashes `callback` in thread-local storage, replacing any previous callback, and
+                /// returns a function pointer that invokes it. The callback may be re-entered so it
+                /// must be `Fn`; use a `Cell` or `RefCell` for any mutable state.
+                pub fn set<F: Fn(#(#types),*) -> #return_type + 'static>(callback: F) -> #name {
+                    Self::stash().with(|stash| {
+                        *stash.borrow_mut() = ::std::option::Option::Some(::std::rc::Rc::new(callback));
+                    });
+                    Self::stash_trampoline
+                }
+                /// Removes the callback stashed on the current thread. The function pointer returned by
+                /// `set` returns a zeroed value if invoked afterwards.
+                pub fn clear() {
+                    Self::stash().with(|stash| stash.borrow_mut().take());
+                }
+                fn stash() -> &'static ::std::thread::LocalKey<::std::cell::RefCell<::std::option::Option<::std::rc::Rc<dyn Fn(#(#types),*) -> #return_type>>>> {
+                    ::std::thread_local! {
+                        static STASH: ::std::cell::RefCell<::std::option::Option<::std::rc::Rc<dyn Fn(#(#types),*) -> #return_type>>> = ::std::cell::RefCell::new(::std::option::Option::None);
+                    }
+                    &STASH
+                }
+                extern "system" fn stash_trampoline(#(#names: #types),*) -> #return_type {
+                    ::winrt::abort_on_unwind(|| {
+                        let callback = Self::stash().with(|stash| stash.borrow().clone());
+                        match callback {
+                            ::std::option::Option::Some(callback) => callback(#(#names),*),
+                            // It is always safe to form an ABI value from an all-zero byte-pattern
+                            // as this represents the null or default state for every type.
+                            ::std::option::Option::None => unsafe { ::std::mem::zeroed() },
+                        }
+                    })
+                }
+                #context
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn delegate(type_name: &str) -> Delegate32 {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.Win32", type_name));
+
+        match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Delegate32(t) => t,
+            _ => panic!("TypeDefinition not a Win32 delegate"),
+        }
+    }
+
+    #[test]
+    fn test_context_param() {
+        // Unnamed parameters but the trailing LPARAM is the caller's data.
+        let t = delegate("WNDENUMPROC");
+        assert!(t.context_param() == Some(1));
+
+        // Named parameters.
+        let t = delegate("ENUMRESNAMEPROCW");
+        assert!(t.context_param() == Some(3));
+
+        // A window procedure's LPARAM is part of the message and not the caller's data.
+        let t = delegate("WNDPROC");
+        assert!(t.context_param().is_none());
+    }
+}
@@ -109,7 +109,10 @@ impl Enum {
             }
         };
 
+        let attributes = gen_type_attributes(&self.name.def);
+
         quote! {
+            #attributes
             #[allow(non_camel_case_types)]
             #[repr(transparent)]
             pub struct #name(#underlying_type);
@@ -0,0 +1,308 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use std::str::FromStr;
+
+/// Formats generated code so that it's readable without depending on `rustfmt`
+///
+/// This is a simple and deterministic pretty printer for the kind of code the generator produces
+/// rather than a general replacement for `rustfmt`. Items, statements, fields and attributes are
+/// each placed on their own line and indented by their nesting within braces.
+pub fn format(source: &str) -> String {
+    let tokens = TokenStream::from_str(source).expect("Generated code could not be tokenized");
+    let mut formatter = Formatter {
+        output: String::new(),
+        last: Last::Start,
+        last_ident: String::new(),
+        last_ident_is_fn_name: false,
+        generics: Vec::new(),
+        closure_params: false,
+        pending_newline: false,
+    };
+    formatter.stream(tokens, 0, true);
+    formatter.output.push('\n');
+    formatter.output
+}
+
+struct Formatter {
+    output: String,
+    // The kind of the last token written, used to decide whether a space is needed.
+    last: Last,
+    // The text of the last identifier and whether it followed `fn`, to tell generics from comparisons.
+    last_ident: String,
+    last_ident_is_fn_name: bool,
+    // The generic argument lists currently open and whether each is the generic parameters of an
+    // `impl` block.
+    generics: Vec<bool>,
+    // Whether the parameters of a closure are being written.
+    closure_params: bool,
+    // Set after a brace group or attribute so that the next token starts a new line.
+    pending_newline: bool,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Last {
+    // The start of a line or the start of a group's contents.
+    Start,
+    Ident,
+    Keyword,
+    Literal,
+    // A punctuation character along with whether it's joined to the next one and whether it's
+    // written directly before the next token, as a prefix operator such as `&` in `&self` is.
+    Punct(char, Spacing, bool),
+    // The `>` closing a list of generic arguments.
+    GenericClose,
+    Close,
+}
+
+// Keywords that are followed by a space rather than being treated as function names.
+const KEYWORDS: &[&str] = &[
+    "as", "dyn", "else", "fn", "for", "if", "impl", "in", "let", "match", "move", "mut", "return",
+    "unsafe", "use", "where", "while",
+];
+
+impl Formatter {
+    fn stream(&mut self, tokens: TokenStream, indent: usize, is_block: bool) {
+        for token in tokens {
+            if self.pending_newline {
+                self.pending_newline = false;
+
+                let continues = match &token {
+                    TokenTree::Punct(p) => ",;.?".contains(p.as_char()),
+                    TokenTree::Ident(i) => i == "else" || i == "as",
+                    _ => false,
+                };
+
+                if !continues {
+                    self.newline(indent);
+                }
+            }
+
+            match token {
+                TokenTree::Group(group) => {
+                    let delimiter = group.delimiter();
+                    let is_attribute = delimiter == Delimiter::Bracket
+                        && matches!(
+                            self.last,
+                            Last::Punct('#', _, _) | Last::Punct('!', _, true)
+                        );
+
+                    // Use groups such as `use a::{b, c};` are kept on one line.
+                    let is_use_group = matches!(self.last, Last::Punct(':', _, true));
+
+                    if delimiter == Delimiter::Brace && !is_use_group {
+                        if self.last != Last::Start {
+                            self.output.push(' ');
+                        }
+
+                        if group.stream().is_empty() {
+                            self.output.push_str("{}");
+                        } else {
+                            self.output.push('{');
+                            self.newline(indent + 1);
+                            self.stream(group.stream(), indent + 1, true);
+                            self.pending_newline = false;
+                            self.newline(indent);
+                            self.output.push('}');
+                        }
+
+                        self.last = Last::Close;
+                        self.pending_newline = is_block;
+                    } else {
+                        let (open, close) = match delimiter {
+                            Delimiter::Parenthesis => ('(', ')'),
+                            Delimiter::Bracket => ('[', ']'),
+                            Delimiter::Brace => ('{', '}'),
+                            Delimiter::None => (' ', ' '),
+                        };
+
+                        let is_call =
+                            matches!(self.last, Last::Ident | Last::Close | Last::GenericClose)
+                                || is_attribute;
+
+                        if !is_call {
+                            self.space();
+                        }
+
+                        self.output.push(open);
+                        self.last = Last::Start;
+                        self.stream(group.stream(), indent, false);
+                        self.output.push(close);
+                        self.last = Last::Close;
+
+                        // Attributes are placed on their own line before the item they apply to.
+                        if is_attribute && is_block {
+                            self.pending_newline = true;
+                        }
+                    }
+                }
+                TokenTree::Ident(ident) => {
+                    self.space();
+
+                    let text = ident.to_string();
+                    self.last_ident_is_fn_name =
+                        self.last == Last::Keyword && self.last_ident == "fn";
+                    self.last = if KEYWORDS.contains(&text.as_str()) {
+                        Last::Keyword
+                    } else {
+                        Last::Ident
+                    };
+                    self.output.push_str(&text);
+                    self.last_ident = text;
+                }
+                TokenTree::Literal(literal) => {
+                    self.space();
+                    self.output.push_str(&literal.to_string());
+                    self.last = Last::Literal;
+                }
+                TokenTree::Punct(punct) => self.punct(punct.as_char(), punct.spacing(), is_block),
+            }
+        }
+    }
+
+    fn punct(&mut self, c: char, spacing: Spacing, is_block: bool) {
+        let is_joined = matches!(self.last, Last::Punct(_, Spacing::Joint, _));
+        let is_operand = matches!(
+            self.last,
+            Last::Ident | Last::Literal | Last::Close | Last::GenericClose | Last::Punct('?', _, _)
+        );
+        let is_path = matches!(self.last, Last::Punct(':', _, true));
+
+        // Generic arguments follow a type name, a path separator or `impl`, and a function name in
+        // its declaration, while a comparison follows a value.
+        let opens_generics = c == '<'
+            && (is_path
+                || (!is_joined
+                    && (self.last == Last::Start
+                        || (self.last == Last::Keyword && self.last_ident == "impl")
+                        || (self.last == Last::Ident
+                            && (self.last_ident_is_fn_name
+                                || self.last_ident.starts_with(char::is_uppercase))))));
+
+        let closes_generics = c == '>' && !is_joined && !self.generics.is_empty();
+
+        // The generic parameters of an `impl` block are followed by a space as a keyword is.
+        let closes_impl_generics = if opens_generics {
+            self.generics.push(self.last == Last::Keyword);
+            false
+        } else if closes_generics {
+            self.generics.pop().unwrap()
+        } else {
+            false
+        };
+
+        // A closure's parameters are written as `|a, b|` while `|` is otherwise an operator.
+        let opens_closure = c == '|' && !is_operand && !is_joined && !self.closure_params;
+        let closes_closure = c == '|' && self.closure_params;
+
+        if opens_closure && spacing == Spacing::Alone {
+            self.closure_params = true;
+        } else if closes_closure {
+            self.closure_params = false;
+        }
+
+        let no_space = is_joined
+            || closes_closure
+            || ",;?".contains(c)
+            || (c == '.' && is_operand)
+            || (c == ':' && matches!(self.last, Last::Ident | Last::Close | Last::GenericClose))
+            || (c == '!' && spacing == Spacing::Alone && self.last == Last::Ident)
+            || (opens_generics && matches!(self.last, Last::Ident | Last::Keyword))
+            || closes_generics;
+
+        if !no_space {
+            self.space();
+        }
+
+        self.output.push(c);
+
+        if closes_generics && !closes_impl_generics {
+            self.last = Last::GenericClose;
+        } else {
+            // A prefix operator binds to the operand that follows it, as do macro names, path
+            // separators and generic arguments.
+            let binds = (!is_joined && !is_operand && "&*!-#'".contains(c))
+                || (c == '!' && spacing == Spacing::Alone && self.last == Last::Ident)
+                || (c == '!' && matches!(self.last, Last::Punct('#', _, _)))
+                || (c == ':' && matches!(self.last, Last::Punct(':', Spacing::Joint, _)))
+                || opens_generics
+                || (opens_closure && spacing == Spacing::Alone)
+                || c == '.';
+
+            self.last = Last::Punct(c, spacing, binds);
+        }
+
+        if is_block && (c == ';' || (c == ',' && self.generics.is_empty())) {
+            self.pending_newline = true;
+        }
+    }
+
+    // Writes a space unless the last token binds to the next one.
+    fn space(&mut self) {
+        let binds = matches!(
+            self.last,
+            Last::Start | Last::Punct(_, Spacing::Joint, _) | Last::Punct(_, _, true)
+        );
+
+        if !binds {
+            self.output.push(' ');
+        }
+    }
+
+    fn newline(&mut self, indent: usize) {
+        // Trailing whitespace is never written so that the output is stable.
+        while self.output.ends_with(' ') {
+            self.output.pop();
+        }
+
+        self.output.push('\n');
+
+        for _ in 0..indent {
+            self.output.push_str("    ");
+        }
+
+        self.last = Last::Start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let source = "# [repr (transparent)] pub struct Colors { ptr : :: winrt :: RawPtr , } \
+                      use a :: { b , c } ; pub use :: winrt :: foundation ; impl < 'a , T > Into < T > for Colors { } impl Colors { \
+                      pub fn red () -> :: winrt :: Result < Color > { let this = & self ; \
+                      this . map (| a , b | a | b) ; \
+                      if this . ptr . is_null () { panic ! (\"{}\" , 1) ; } else { Ok (* this) } } }";
+
+        assert_eq!(
+            format(source),
+            r#"#[repr(transparent)]
+pub struct Colors {
+    ptr: ::winrt::RawPtr,
+}
+use a::{b, c};
+pub use ::winrt::foundation;
+impl<'a, T> Into<T> for Colors {}
+impl Colors {
+    pub fn red() -> ::winrt::Result<Color> {
+        let this = &self;
+        this.map(|a, b| a | b);
+        if this.ptr.is_null() {
+            panic!("{}", 1);
+        } else {
+            Ok(*this)
+        }
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let source = "# ! [allow (unused)] pub mod windows { pub mod ui { pub struct A { } \
+                      impl < T : :: winrt :: RuntimeType > :: std :: iter :: Iterator for A { } } }";
+        assert_eq!(format(source), format(&format(source)));
+    }
+}
@@ -59,11 +59,43 @@ fn gen_async_kind(
         _ => panic!("Unexpected AsyncKind"),
     };
 
+    let with_progress = match kind {
+        AsyncKind::ActionWithProgress | AsyncKind::OperationWithProgress => {
+            let progress_handler = if kind == AsyncKind::ActionWithProgress {
+                quote! { AsyncActionProgressHandler }
+            } else {
+                quote! { AsyncOperationProgressHandler }
+            };
+
+            let generics = name.generics.iter().map(|g| g.gen());
+            let progress = name.generics.last().unwrap().gen();
+
+            quote! {
+                pub fn with_progress(&self) -> ::winrt::Result<(::winrt::AsyncFuture<Self>, ::winrt::AsyncProgress<<#progress as ::winrt::RuntimeType>::DefaultType>)>
+                where
+                    <#progress as ::winrt::RuntimeType>::DefaultType: ::std::clone::Clone,
+                {
+                    let mut future = ::winrt::AsyncFuture::new(::std::clone::Clone::clone(self));
+                    let (progress, mut report) = future.progress()?;
+
+                    self.set_progress(::winrt::foundation:: #progress_handler::<#(#generics),*>::new(move |_sender, value| {
+                        report(::std::clone::Clone::clone(value));
+                        Ok(())
+                    }))?;
+
+                    Ok((future, progress))
+                }
+            }
+        }
+        _ => TokenStream::new(),
+    };
+
     let constraints = self_name.gen_constraint();
     let name = self_name.gen();
 
     (
         quote! {
+            #with_progress
             pub fn get(&self) -> ::winrt::Result<#return_type> {
                 if self.status()? == ::winrt::foundation::AsyncStatus::Started {
                     let (waiter, signaler) = ::winrt::Waiter::new();
@@ -77,24 +109,83 @@ fn gen_async_kind(
             }
         },
         quote! {
-            impl<#constraints> ::std::future::Future for #name {
-                type Output = ::winrt::Result<#return_type>;
+            impl<#constraints> ::winrt::Async for #name {
+                type Output = #return_type;
 
-                fn poll(self: ::std::pin::Pin<&mut Self>, context: &mut ::std::task::Context) -> ::std::task::Poll<Self::Output> {
-                    if self.status()? == ::winrt::foundation::AsyncStatus::Started {
-                        let waker = context.waker().clone();
+                fn info(&self) -> ::winrt::foundation::IAsyncInfo {
+                    ::std::convert::From::from(self)
+                }
 
-                        let _ = self.set_completed(::winrt::foundation:: #handler::new(move |_sender, _args| {
-                            waker.wake_by_ref();
-                            Ok(())
-                        }));
+                fn set_completed_callback<F: FnMut() + 'static>(&self, mut callback: F) -> ::winrt::Result<()> {
+                    self.set_completed(::winrt::foundation:: #handler::new(move |_sender, _args| {
+                        callback();
+                        Ok(())
+                    }))
+                }
 
-                        ::std::task::Poll::Pending
-                    } else {
-                        ::std::task::Poll::Ready(self.get_results())
-                    }
+                fn results(&self) -> ::winrt::Result<#return_type> {
+                    self.get_results()
+                }
+            }
+            impl<#constraints> ::std::future::IntoFuture for #name {
+                type Output = ::winrt::Result<#return_type>;
+                type IntoFuture = ::winrt::AsyncFuture<Self>;
+
+                fn into_future(self) -> Self::IntoFuture {
+                    ::winrt::AsyncFuture::new(self)
                 }
             }
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_async_future() {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.Foundation", "IAsyncAction"));
+
+        let t = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Interface(t) => t,
+            _ => panic!("TypeDefinition not an interface"),
+        };
+
+        let (get, future) = gen_async(&t.name, &t.interfaces);
+        let future = future.into_string();
+
+        assert!(get.into_string().contains("fn get"));
+        assert!(future.contains("IntoFuture"));
+        assert!(future.contains("AsyncFuture"));
+        assert!(future.contains("set_completed_callback"));
+        assert!(!future.contains("fn poll"));
+    }
+
+    #[test]
+    fn test_async_progress() {
+        let reader = &winmd::TypeReader::from_build();
+
+        let def = reader.resolve_type_def(("Windows.Foundation", "IAsyncAction"));
+        let t = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Interface(t) => t,
+            _ => panic!("TypeDefinition not an interface"),
+        };
+
+        let (methods, _) = gen_async(&t.name, &t.interfaces);
+        assert!(!methods.into_string().contains("with_progress"));
+
+        let def = reader.resolve_type_def(("Windows.Foundation", "IAsyncOperationWithProgress`2"));
+        let t = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Interface(t) => t,
+            _ => panic!("TypeDefinition not an interface"),
+        };
+
+        let (methods, _) = gen_async(&t.name, &t.interfaces);
+        let methods = methods.into_string();
+        assert!(methods.contains("with_progress"));
+        assert!(methods.contains("AsyncOperationProgressHandler"));
+        assert!(methods.contains("set_progress"));
+    }
+}
@@ -0,0 +1,372 @@
+use crate::*;
+use rayon::iter::ParallelIterator;
+
+/// The namespaces provided by the `winrt` crate itself
+pub const FOUNDATION_NAMESPACES: &[&str] = &[
+    "Windows.Foundation",
+    "Windows.Foundation.Collections",
+    "Windows.Foundation.Diagnostics",
+    "Windows.Foundation.Numerics",
+];
+
+/// Generates bindings for a set of types, producing the same code as the `build!` macro
+///
+/// ```rust,ignore
+/// let reader = winmd::TypeReader::from_build();
+/// let mut limits = TypeLimits::new(reader);
+/// limits.insert(NamespaceTypes {
+///     namespace: "windows.ui".to_owned(),
+///     limit: TypeLimit::Some(vec!["Colors".to_owned()]),
+/// })?;
+///
+/// Generator::new(reader).limits(limits).write_to("src/bindings.rs")?;
+/// ```
+///
+/// Large selections can instead be written to a file per namespace with
+/// [`write_to_dir`](Generator::write_to_dir), optionally gating each namespace behind a cargo
+/// feature so that a single bindings crate can serve many consumers.
+pub struct Generator {
+    reader: &'static winmd::TypeReader,
+    limits: Option<TypeLimits<'static>>,
+    features: bool,
+}
+
+impl Generator {
+    pub fn new(reader: &'static winmd::TypeReader) -> Self {
+        Self {
+            reader,
+            limits: None,
+            features: false,
+        }
+    }
+
+    /// The types to generate along with their dependencies
+    ///
+    /// Without any limits only the foundation namespaces are generated, as for the `winrt` crate.
+    pub fn limits(mut self, limits: TypeLimits<'static>) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Gates each namespace behind a cargo feature when writing a file per namespace
+    ///
+    /// The features are named after the namespaces, as in `Windows_UI_Xaml`.
+    pub fn features(mut self, features: bool) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Generates the bindings as source code formatted by [`format`]
+    pub fn gen(self) -> String {
+        gen_tree(&self.tree())
+    }
+
+    /// Generates the bindings as source code formatted by [`format`] along with a [`Fingerprint`] of the
+    /// inputs they were generated from
+    pub fn gen_with_fingerprint(self) -> (String, Fingerprint) {
+        let (tree, fingerprint) = self.tree_with_fingerprint();
+        (gen_tree(&tree), fingerprint)
+    }
+
+    /// Generates the bindings as [`gen_with_fingerprint`](Generator::gen_with_fingerprint) does,
+    /// reusing the bindings cached in the directory by an earlier call with the same limits,
+    /// metadata and generator version
+    ///
+    /// The cache is looked up before the types are resolved, so its key covers all of the metadata
+    /// rather than only the files that the generated types are defined in. The fingerprint is
+    /// cached along with the bindings, and bindings that haven't been written for a week are
+    /// removed from the directory.
+    pub fn gen_cached<P: AsRef<std::path::Path>>(self, dir: P) -> (String, Fingerprint) {
+        let dir = dir.as_ref();
+        let key = self.cache_key();
+        let path = dir.join(format!("{}.rs", key));
+
+        // The cache only saves time so failing to read or write it isn't an error.
+        if let Some(cached) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|cached| read_cached(&cached))
+        {
+            return cached;
+        }
+
+        let (tree, fingerprint) = self.tree_with_fingerprint();
+        let code = gen_tree(&tree);
+
+        // The fingerprint is written as comments ahead of the bindings.
+        let mut cached = format!("// {}\n", fingerprint.hash);
+
+        for file in &fingerprint.files {
+            cached.push_str(&format!("// {}\n", file.display()));
+        }
+
+        cached.push_str(&code);
+
+        // The bindings are written to a temporary file first so that other processes sharing the
+        // cache never read a partially written file.
+        let temp = dir.join(format!("{}.{}.tmp", key, std::process::id()));
+
+        if std::fs::create_dir_all(dir).is_ok() && std::fs::write(&temp, &cached).is_ok() {
+            let _ = std::fs::rename(&temp, &path);
+        }
+
+        prune_cache(dir);
+        (code, fingerprint)
+    }
+
+    // A hash of the limits, the contents of all of the metadata files and the generator's version
+    // and sources.
+    fn cache_key(&self) -> String {
+        let mut hash = sha1::Sha1::new();
+        hash.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hash.update(env!("WINRT_GEN_SOURCE_HASH").as_bytes());
+
+        if let Some(limits) = &self.limits {
+            hash_limits(&mut hash, limits);
+        }
+
+        for file in &self.reader.files {
+            hash.update(file.bytes());
+        }
+
+        hash.digest().to_string()
+    }
+
+    fn tree_with_fingerprint(self) -> (TypeTree, Fingerprint) {
+        let reader = self.reader;
+        let mut hash = sha1::Sha1::new();
+        hash.update(env!("CARGO_PKG_VERSION").as_bytes());
+
+        if let Some(limits) = &self.limits {
+            hash_limits(&mut hash, limits);
+        }
+
+        let tree = self.tree();
+        let mut files = Vec::new();
+
+        for index in tree.files() {
+            let file = &reader.files[index as usize];
+            hash.update(file.bytes());
+            files.push(file.path.clone());
+        }
+
+        let fingerprint = Fingerprint {
+            hash: hash.digest().to_string(),
+            files,
+        };
+
+        (tree, fingerprint)
+    }
+
+    fn tree(self) -> TypeTree {
+        let mut limits = match self.limits {
+            Some(limits) => limits,
+            None => TypeLimits::new(self.reader),
+        };
+
+        let is_foundation = limits.inner.is_empty() && limits.members.is_empty();
+
+        if is_foundation {
+            for namespace in FOUNDATION_NAMESPACES {
+                limits
+                    .insert(NamespaceTypes {
+                        namespace: namespace.to_string(),
+                        limit: TypeLimit::All,
+                    })
+                    .unwrap();
+            }
+        }
+
+        let mut tree = TypeTree::from_limits(self.reader, &limits);
+
+        if !is_foundation {
+            for namespace in FOUNDATION_NAMESPACES {
+                tree.remove(namespace);
+            }
+
+            tree.reexport();
+        }
+
+        for (namespace, path) in &limits.externs {
+            tree.insert_extern(namespace, path);
+        }
+
+        tree
+    }
+
+    /// Writes the bindings to the file at the path
+    pub fn write_to<P: AsRef<std::path::Path>>(self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.gen())
+    }
+
+    /// Writes the bindings to a file per namespace in the directory
+    ///
+    /// The directory's `mod.rs` declares the top-level namespaces and each namespace's `mod.rs`
+    /// declares its nested namespaces. With [`features`](Generator::features) the `[features]`
+    /// table to add to the bindings crate's Cargo.toml is also written to `features.toml`.
+    pub fn write_to_dir<P: AsRef<std::path::Path>>(self, dir: P) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        let features = self.features;
+        let tree = self.tree();
+
+        for (path, tokens) in tree.gen_files(features) {
+            let path = dir.join(path);
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::write(&path, format(&tokens.into_string()))?;
+        }
+
+        if features {
+            std::fs::write(dir.join("features.toml"), tree.cargo_features())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn hash_limits(hash: &mut sha1::Sha1, limits: &TypeLimits) {
+    hash.update(
+        format!(
+            "{:?}{:?}{:?}{:?}{:?}{:?}",
+            limits.inner,
+            limits.excluded,
+            limits.renames,
+            limits.members,
+            limits.contracts,
+            limits.externs
+        )
+        .as_bytes(),
+    );
+}
+
+// Removes the cached bindings that haven't been written for a week so that the cache doesn't keep
+// growing as the limits, metadata and generator change. Bindings that are still used are simply
+// generated again.
+fn prune_cache(dir: &std::path::Path) {
+    const MAX_AGE: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 60 * 60);
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let is_cache_file = matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("rs") | Some("tmp")
+        );
+
+        let age = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if is_cache_file && matches!(age, Some(age) if age > MAX_AGE) {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+// Splits cached bindings into the bindings and the fingerprint written as comments ahead of them.
+fn read_cached(cached: &str) -> Option<(String, Fingerprint)> {
+    let mut rest = cached;
+    let mut header = Vec::new();
+
+    while let Some(line) = rest.strip_prefix("// ") {
+        let end = line.find('\n')?;
+        header.push(&line[..end]);
+        rest = &line[end + 1..];
+    }
+
+    let (hash, files) = header.split_first()?;
+
+    let fingerprint = Fingerprint {
+        hash: hash.to_string(),
+        files: files.iter().map(std::path::PathBuf::from).collect(),
+    };
+
+    Some((rest.to_string(), fingerprint))
+}
+
+fn gen_tree(tree: &TypeTree) -> String {
+    let ts = tree.gen().reduce(squote::TokenStream::new, |mut accum, n| {
+        accum.combine(&n);
+        accum
+    });
+
+    format(&ts.into_string())
+}
+
+/// Identifies the inputs that bindings were generated from so that regenerating them can be skipped
+/// when nothing has changed
+pub struct Fingerprint {
+    /// A hash of the type limits, the contents of the metadata files used and the generator version
+    pub hash: String,
+    /// The metadata files that the generated types are defined in
+    pub files: Vec<std::path::PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn generator(types: &[&str]) -> Generator {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.ui".to_owned(),
+                limit: TypeLimit::Some(types.iter().map(|t| t.to_string()).collect()),
+            })
+            .unwrap();
+
+        Generator::new(reader).limits(limits)
+    }
+
+    fn fingerprint(types: &[&str]) -> Fingerprint {
+        generator(types).gen_with_fingerprint().1
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let colors = fingerprint(&["Colors"]);
+        assert!(!colors.files.is_empty());
+
+        assert_eq!(colors.hash, fingerprint(&["Colors"]).hash);
+        assert_ne!(colors.hash, fingerprint(&["Colors", "ColorHelper"]).hash);
+    }
+
+    #[test]
+    fn test_gen_cached() {
+        let dir = std::env::temp_dir().join(format!("winrt-gen-cache-{}", std::process::id()));
+        let (code, fingerprint) = generator(&["Colors"]).gen_cached(&dir);
+
+        let cached = std::fs::read_dir(&dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let contents = std::fs::read_to_string(&cached).unwrap();
+        assert!(contents.starts_with(&format!("// {}\n", fingerprint.hash)));
+        assert!(contents.ends_with(&code));
+
+        // The cached bindings and fingerprint are returned rather than generated again.
+        std::fs::write(
+            &cached,
+            format!("// {}\n// a.winmd\ncached", fingerprint.hash),
+        )
+        .unwrap();
+        let (code, cached) = generator(&["Colors"]).gen_cached(&dir);
+        assert_eq!(code, "cached");
+        assert_eq!(cached.hash, fingerprint.hash);
+        assert_eq!(cached.files, [std::path::PathBuf::from("a.winmd")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -38,7 +38,11 @@ impl Interface {
             dependencies.append(&mut interface.name.dependencies());
 
             if interface.kind == InterfaceKind::Default {
-                for method in &interface.methods {
+                for method in interface
+                    .methods
+                    .iter()
+                    .filter(|method| method.is_available)
+                {
                     dependencies.append(&mut method.dependencies());
                 }
             }
@@ -74,6 +78,11 @@ impl Interface {
         let methods = gen_method(&self.interfaces);
 
         let abi_methods = default_interface.methods.iter().map(|method| {
+            // Unavailable methods keep their place in the vtable without naming their parameter types.
+            if !method.is_available {
+                return quote! { pub usize };
+            }
+
             let signature = method.gen_abi();
 
             quote! {
@@ -82,9 +91,12 @@ impl Interface {
         });
 
         let iterator = gen_iterator(&self.name, &self.interfaces);
+        let collections = gen_collections(&self.name);
         let (async_get, future) = gen_async(&self.name, &self.interfaces);
+        let attributes = gen_type_attributes(&self.name.def);
 
         quote! {
+            #attributes
             #[repr(transparent)]
             pub struct #definition(::winrt::Object, #phantoms) where #constraints;
             impl<#constraints> ::std::clone::Clone for #name {
@@ -149,6 +161,7 @@ impl Interface {
             }
             #(#conversions)*
             #iterator
+            #collections
             #future
         }
     }
@@ -4,10 +4,12 @@ use squote::{quote, TokenStream};
 // Provides iterator support for the well-known WinRT collection interfaces and any classes or
 // interfaces that implement any of these interfaces. It also favors high-speed iteration and
 // only falls back to IIterator<T> if nothing faster is available. VectorIterator and
-// VectorViewIterator are faster iterators than IIterator<T> because they only require a single
-// vcall per iteration wheras IIterator<T> requires two.
+// VectorViewIterator are faster iterators than IIterator<T> because they know the size of the
+// collection up front. All of them retrieve items in chunks with GetMany rather than making one
+// or two vcalls per item.
 pub fn gen_iterator(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenStream {
-    // If the type is IIterator<T> then simply implement the Iterator trait over top.
+    // If the type is IIterator<T> then implement the Iterator trait over top and provide the
+    // ChunkedIterator for retrieving items with GetMany.
     if name.name == "IIterator`1" && name.namespace == "Windows.Foundation.Collections" {
         return quote! {
             impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for IIterator<T> {
@@ -23,128 +25,122 @@ pub fn gen_iterator(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenS
                     result
                 }
             }
-        };
-    }
-
-    // If the type is IIterable<T> then implement the IntoIterator trait and rely on the resulting
-    // IIterator<T> returned by first() to implement the Iterator trait.
-    if name.name == "IIterable`1" && name.namespace == "Windows.Foundation.Collections" {
-        return quote! {
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IIterable<T> {
-                type Item = T;
-                type IntoIter = IIterator<Self::Item>;
 
-                fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
+            impl<T: ::winrt::RuntimeType> IIterator<T> {
+                pub fn chunked(self, chunk_size: u32) -> ChunkedIterator<T> {
+                    ChunkedIterator::new(self, chunk_size)
                 }
             }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IIterable<T> {
-                type Item = T;
-                type IntoIter = IIterator<Self::Item>;
 
-                fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
-                }
+            pub struct ChunkedIterator<T: ::winrt::RuntimeType + 'static> {
+                iterator: IIterator<T>,
+                chunk_size: u32,
+                items: ::std::collections::VecDeque<T>,
+                done: bool,
             }
-        };
-    }
 
-    // If the type is IVectorView<T> then provide the VectorViewIterator fast iterator.
-    if name.name == "IVectorView`1" && name.namespace == "Windows.Foundation.Collections" {
-        return quote! {
-            pub struct VectorViewIterator<T: ::winrt::RuntimeType + 'static> {
-                vector: IVectorView<T>,
-                current: u32,
-                size: u32,
-            }
-
-            impl<T: ::winrt::RuntimeType> VectorViewIterator<T> {
-                pub fn new(vector: IVectorView<T>) -> Self {
-                    let size = vector.size().unwrap();
-                    Self { vector, current: 0, size }
+            impl<T: ::winrt::RuntimeType> ChunkedIterator<T> {
+                pub fn new(iterator: IIterator<T>, chunk_size: u32) -> Self {
+                    assert!(chunk_size > 0, "The chunk size must be non-zero");
+                    Self { iterator, chunk_size, items: ::std::collections::VecDeque::new(), done: false }
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorViewIterator<T> {
+            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for ChunkedIterator<T> {
                 type Item = T;
 
                 fn next(&mut self) -> ::std::option::Option<Self::Item> {
-                    if self.current >= self.size {
-                        return None;
+                    if self.items.is_empty() && !self.done {
+                        let iterator = &self.iterator;
+
+                        match ::winrt::get_chunk(self.chunk_size, |items| iterator.get_many(items)) {
+                            ::std::result::Result::Ok(items) if !items.is_empty() => self.items = items,
+                            _ => self.done = true,
+                        }
                     }
 
-                    let result = self.vector.get_at(self.current);
-                    self.current += 1;
-                    result.ok()
+                    self.items.pop_front()
                 }
             }
+        };
+    }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVectorView<T> {
+    // If the type is IIterable<T> then implement the IntoIterator trait and rely on the resulting
+    // IIterator<T> returned by first() to retrieve items in chunks.
+    if name.name == "IIterable`1" && name.namespace == "Windows.Foundation.Collections" {
+        return quote! {
+            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IIterable<T> {
                 type Item = T;
-                type IntoIter = VectorViewIterator<Self::Item>;
+                type IntoIter = ChunkedIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    VectorViewIterator::new(self)
+                    self.first().unwrap().chunked(::winrt::DEFAULT_CHUNK_SIZE)
                 }
             }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVectorView<T> {
+            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IIterable<T> {
                 type Item = T;
-                type IntoIter = VectorViewIterator<Self::Item>;
+                type IntoIter = ChunkedIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    VectorViewIterator::new(::std::clone::Clone::clone(self))
+                    self.first().unwrap().chunked(::winrt::DEFAULT_CHUNK_SIZE)
                 }
             }
         };
     }
 
+    // If the type is IVectorView<T> then provide the VectorViewIterator fast iterator.
+    if name.name == "IVectorView`1" && name.namespace == "Windows.Foundation.Collections" {
+        return gen_vector_iterator(&quote! { IVectorView }, &quote! { VectorViewIterator });
+    }
+
     // If the type is IVector<T> then provide the VectorIterator fast iterator.
     if name.name == "IVector`1" && name.namespace == "Windows.Foundation.Collections" {
-        return quote! {
-            pub struct VectorIterator<T: ::winrt::RuntimeType + 'static> {
-                vector: IVector<T>,
-                current: u32,
-                size: u32,
+        return gen_vector_iterator(&quote! { IVector }, &quote! { VectorIterator });
+    }
+
+    // If the type is IMapView<K, V> then provide the KeyValueIterator, which yields each of the
+    // IKeyValuePair<K, V> items as a tuple.
+    if name.name == "IMapView`2" && name.namespace == "Windows.Foundation.Collections" {
+        let iterator = quote! {
+            /// Yields the key and value of each pair, ending early if either can't be retrieved.
+            /// Use `try_next` to find out why instead.
+            pub struct KeyValueIterator<K: ::winrt::RuntimeType + 'static, V: ::winrt::RuntimeType + 'static> {
+                iterator: ChunkedIterator<IKeyValuePair<K, V>>,
             }
 
-            impl<T: ::winrt::RuntimeType> VectorIterator<T> {
-                pub fn new(vector: IVector<T>) -> Self {
-                    let size = vector.size().unwrap();
-                    Self { vector, current: 0, size }
+            impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> KeyValueIterator<K, V> {
+                pub fn new(iterator: IIterator<IKeyValuePair<K, V>>) -> Self {
+                    Self { iterator: iterator.chunked(::winrt::DEFAULT_CHUNK_SIZE) }
+                }
+
+                /// Takes the key and value of the next pair, or the error if either can't be retrieved.
+                pub fn try_next(&mut self) -> ::std::option::Option<::winrt::Result<(K, V)>> {
+                    let pair = self.iterator.next()?;
+                    ::std::option::Option::Some(pair.key().and_then(|key| ::std::result::Result::Ok((key, pair.value()?))))
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorIterator<T> {
-                type Item = T;
+            impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> ::std::iter::Iterator for KeyValueIterator<K, V> {
+                type Item = (K, V);
 
                 fn next(&mut self) -> ::std::option::Option<Self::Item> {
-                    if self.current >= self.size {
-                        return None;
-                    }
-
-                    let result = self.vector.get_at(self.current);
-                    self.current += 1;
-                    result.ok()
+                    self.try_next()?.ok()
                 }
             }
+        };
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVector<T> {
-                type Item = T;
-                type IntoIter = VectorIterator<Self::Item>;
+        let into_iterator = gen_key_value_iterator(&quote! { IMapView });
+        return quote! { #iterator #into_iterator };
+    }
 
-                fn into_iter(self) -> Self::IntoIter {
-                    VectorIterator::new(self)
-                }
-            }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVector<T> {
-                type Item = T;
-                type IntoIter = VectorIterator<Self::Item>;
+    // If the type is IMap<K, V> then rely on the KeyValueIterator.
+    if name.name == "IMap`2" && name.namespace == "Windows.Foundation.Collections" {
+        return gen_key_value_iterator(&quote! { IMap });
+    }
 
-                fn into_iter(self) -> Self::IntoIter {
-                    VectorIterator::new(::std::clone::Clone::clone(self))
-                }
-            }
-        };
+    // If the type is IObservableMap<K, V> then rely on the KeyValueIterator.
+    if name.name == "IObservableMap`2" && name.namespace == "Windows.Foundation.Collections" {
+        return gen_key_value_iterator(&quote! { IObservableMap });
     }
 
     let mut iterable = None;
@@ -179,7 +175,7 @@ pub fn gen_iterator(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenS
             };
         }
 
-        if interface.name.name == "IVectorView`1"
+        if interface.name.name == "IVector`1"
             && interface.name.namespace == "Windows.Foundation.Collections"
         {
             let item = interface.name.generics[0].gen();
@@ -224,21 +220,205 @@ pub fn gen_iterator(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenS
             quote! {
                impl<#constraints> ::std::iter::IntoIterator for #name {
                     type Item = #item;
-                    type IntoIter = #wfc IIterator<Self::Item>;
+                    type IntoIter = #wfc ChunkedIterator<Self::Item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        self.first().unwrap().chunked(::winrt::DEFAULT_CHUNK_SIZE)
                     }
                 }
                 impl<'a, #constraints> ::std::iter::IntoIterator for &'a #name {
                     type Item = #item;
-                    type IntoIter = #wfc IIterator<Self::Item>;
+                    type IntoIter = #wfc ChunkedIterator<Self::Item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        self.first().unwrap().chunked(::winrt::DEFAULT_CHUNK_SIZE)
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The vector iterators retrieve items in chunks from either end of the vector so that they can be
+// double-ended. The front chunk is retrieved from the indices in front..back and the back chunk
+// from the end of that range, so each item is retrieved once no matter which end it's taken from.
+fn gen_vector_iterator(interface: &TokenStream, iterator: &TokenStream) -> TokenStream {
+    quote! {
+        pub struct #iterator<T: ::winrt::RuntimeType + 'static> {
+            vector: #interface<T>,
+            front: u32,
+            back: u32,
+            chunk_size: u32,
+            front_items: ::std::collections::VecDeque<T>,
+            back_items: ::std::collections::VecDeque<T>,
+        }
+
+        impl<T: ::winrt::RuntimeType> #iterator<T> {
+            pub fn new(vector: #interface<T>) -> Self {
+                Self::with_chunk_size(vector, ::winrt::DEFAULT_CHUNK_SIZE)
+            }
+
+            pub fn with_chunk_size(vector: #interface<T>, chunk_size: u32) -> Self {
+                assert!(chunk_size > 0, "The chunk size must be non-zero");
+                let back = vector.size().unwrap();
+
+                Self {
+                    vector,
+                    front: 0,
+                    back,
+                    chunk_size,
+                    front_items: ::std::collections::VecDeque::new(),
+                    back_items: ::std::collections::VecDeque::new(),
+                }
+            }
+
+            // Retrieves `count` items starting at `start`. If the vector has changed and fewer
+            // items are retrieved then no more chunks are retrieved.
+            fn get_chunk(&mut self, start: u32, count: u32) -> ::std::collections::VecDeque<T> {
+                let vector = &self.vector;
+
+                match ::winrt::get_chunk(count, |items| vector.get_many(start, items)) {
+                    ::std::result::Result::Ok(items) if items.len() == count as usize => items,
+                    result => {
+                        self.back = self.front;
+                        result.unwrap_or_default()
                     }
                 }
             }
         }
+
+        impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for #iterator<T> {
+            type Item = T;
+
+            fn next(&mut self) -> ::std::option::Option<Self::Item> {
+                if self.front_items.is_empty() && self.front < self.back {
+                    let count = ::std::cmp::min(self.chunk_size, self.back - self.front);
+                    let start = self.front;
+                    self.front += count;
+                    self.front_items = self.get_chunk(start, count);
+                }
+
+                self.front_items.pop_front().or_else(|| self.back_items.pop_front())
+            }
+
+            fn size_hint(&self) -> (usize, ::std::option::Option<usize>) {
+                let len = (self.back - self.front) as usize + self.front_items.len() + self.back_items.len();
+                (len, ::std::option::Option::Some(len))
+            }
+        }
+
+        impl<T: ::winrt::RuntimeType> ::std::iter::DoubleEndedIterator for #iterator<T> {
+            fn next_back(&mut self) -> ::std::option::Option<Self::Item> {
+                if self.back_items.is_empty() && self.front < self.back {
+                    let count = ::std::cmp::min(self.chunk_size, self.back - self.front);
+                    self.back -= count;
+                    let start = self.back;
+                    self.back_items = self.get_chunk(start, count);
+                }
+
+                self.back_items.pop_back().or_else(|| self.front_items.pop_back())
+            }
+        }
+
+        impl<T: ::winrt::RuntimeType> ::std::iter::ExactSizeIterator for #iterator<T> {}
+
+        impl<T: ::winrt::RuntimeType> #interface<T> {
+            pub fn to_vec(&self) -> ::winrt::Result<::std::vec::Vec<T>> {
+                let size = self.size()?;
+                let items = ::winrt::get_chunk(size, |items| self.get_many(0, items))?;
+                ::std::result::Result::Ok(items.into())
+            }
+        }
+
+        impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for #interface<T> {
+            type Item = T;
+            type IntoIter = #iterator<Self::Item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator::new(self)
+            }
+        }
+        impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a #interface<T> {
+            type Item = T;
+            type IntoIter = #iterator<Self::Item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                #iterator::new(::std::clone::Clone::clone(self))
+            }
+        }
+    }
+}
+
+fn gen_key_value_iterator(interface: &TokenStream) -> TokenStream {
+    quote! {
+        impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> ::std::iter::IntoIterator for #interface<K, V> {
+            type Item = (K, V);
+            type IntoIter = KeyValueIterator<K, V>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                KeyValueIterator::new(self.first().unwrap())
+            }
+        }
+        impl<'a, K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a #interface<K, V> {
+            type Item = (K, V);
+            type IntoIter = KeyValueIterator<K, V>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                KeyValueIterator::new(self.first().unwrap())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn interface(namespace: &str, name: &str) -> Interface {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def((namespace, name));
+
+        match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Interface(t) => t,
+            _ => panic!("TypeDefinition not an interface"),
+        }
+    }
+
+    #[test]
+    fn test_vector_iterator() {
+        let t = interface("Windows.Foundation.Collections", "IVectorView`1");
+        let iterator = gen_iterator(&t.name, &t.interfaces).into_string();
+
+        assert!(iterator.contains("VectorViewIterator"));
+        assert!(iterator.contains("get_many"));
+        assert!(iterator.contains("DoubleEndedIterator"));
+        assert!(iterator.contains("ExactSizeIterator"));
+        assert!(iterator.contains("fn to_vec"));
+    }
+
+    #[test]
+    fn test_chunked_iterator() {
+        let t = interface("Windows.Foundation.Collections", "IIterator`1");
+        let iterator = gen_iterator(&t.name, &t.interfaces).into_string();
+        assert!(iterator.contains("fn chunked"));
+        assert!(iterator.contains("get_many"));
+
+        let t = interface("Windows.Foundation.Collections", "IIterable`1");
+        let iterator = gen_iterator(&t.name, &t.interfaces).into_string();
+        assert!(iterator.contains("ChunkedIterator"));
+        assert!(iterator.contains("DEFAULT_CHUNK_SIZE"));
+    }
+
+    #[test]
+    fn test_key_value_iterator() {
+        let t = interface("Windows.Foundation.Collections", "IMapView`2");
+        let iterator = gen_iterator(&t.name, &t.interfaces).into_string();
+        assert!(iterator.contains("pub struct KeyValueIterator"));
+        assert!(iterator.contains("pub fn try_next"));
+
+        let t = interface("Windows.Foundation.Collections", "IMap`2");
+        let iterator = gen_iterator(&t.name, &t.interfaces).into_string();
+        assert!(iterator.contains("KeyValueIterator"));
+        assert!(!iterator.contains("pub struct KeyValueIterator"));
     }
 }
@@ -1,10 +1,15 @@
+mod build_limits;
 mod class;
 mod class32;
+mod collections;
+mod contract;
 mod delegate;
 mod delegate32;
 mod r#enum;
+mod format;
 mod format_ident;
 mod futures;
+mod generator;
 mod hex_reader;
 mod interface;
 mod interface32;
@@ -13,6 +18,7 @@ mod iterator;
 mod method;
 mod method_kind;
 mod namespace;
+mod overload;
 mod param;
 mod required_interface;
 mod r#struct;
@@ -26,12 +32,17 @@ mod type_name;
 mod type_namespaces;
 mod type_tree;
 
+pub use build_limits::*;
 pub use class::*;
 pub use class32::*;
+pub use collections::*;
+pub use contract::*;
 pub use delegate::*;
 pub use delegate32::*;
+pub use format::*;
 pub use format_ident::*;
 pub use futures::*;
+pub use generator::*;
 pub use hex_reader::*;
 pub use interface::*;
 pub use interface32::*;
@@ -40,6 +51,7 @@ pub use iterator::*;
 pub use method::*;
 pub use method_kind::*;
 pub use namespace::*;
+pub use overload::*;
 pub use param::*;
 pub use r#enum::*;
 pub use r#struct::*;
@@ -53,3 +65,15 @@ pub use type_limits::*;
 pub use type_name::*;
 pub use type_namespaces::*;
 pub use type_tree::*;
+
+// Snake <-> camel casing is lossy so we go for character but not case conversion
+// and deal with casing once we have an index of namespaces to compare against.
+pub fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
+    let mut result = String::with_capacity(namespace.len());
+    for c in namespace.chars() {
+        if c != '"' && c != '_' {
+            result.extend(c.to_lowercase());
+        }
+    }
+    result
+}
@@ -5,10 +5,19 @@ use std::iter::FromIterator;
 #[derive(Debug)]
 pub struct Method {
     pub name: String,
+    pub kind: MethodKind,
+    /// The name shared by all of the method's overloads
+    pub plain_name: String,
+    pub is_default_overload: bool,
     pub params: Vec<Param>,
     pub return_type: Option<Param>,
     pub vtable_offset: u32,
     pub overload: u32,
+    pub deprecated: Option<Deprecated>,
+    pub introduced: Option<Introduced>,
+    /// `false` if the method was introduced after the targeted contract version or isn't among
+    /// the members selected in `build!`, in which case its vtable entry is only padding
+    pub is_available: bool,
 }
 
 impl Method {
@@ -18,23 +27,33 @@ impl Method {
         generics: &[TypeKind],
         calling_namespace: &'static str,
     ) -> Method {
-        let name = if method.flags().special() {
+        let is_default_overload = method.attributes().any(|attribute| {
+            attribute.name() == ("Windows.Foundation.Metadata", "DefaultOverloadAttribute")
+        });
+
+        let (name, kind) = if method.flags().special() {
             let name = method.name();
 
             if name.starts_with("get") {
-                to_snake(&name[4..], MethodKind::Get)
+                (to_snake(&name[4..], MethodKind::Get), MethodKind::Get)
             } else if name.starts_with("put") {
-                to_snake(&name[4..], MethodKind::Set)
+                (to_snake(&name[4..], MethodKind::Set), MethodKind::Set)
             } else if name.starts_with("add") {
-                to_snake(&name[4..], MethodKind::Add)
+                (to_snake(&name[4..], MethodKind::Add), MethodKind::Add)
             } else if name.starts_with("remove") {
-                to_snake(&name[7..], MethodKind::Remove)
+                (to_snake(&name[7..], MethodKind::Remove), MethodKind::Remove)
             } else {
                 // A delegate's 'Invoke' method is "special" but lacks a preamble.
-                "invoke".to_owned()
+                ("invoke".to_owned(), MethodKind::Normal)
             }
         } else {
-            Method::name(method)
+            (Method::name(method), MethodKind::Normal)
+        };
+
+        let plain_name = if method.flags().special() {
+            name.clone()
+        } else {
+            to_snake(method.name(), MethodKind::Normal)
         };
 
         let mut blob = method.sig();
@@ -95,13 +114,28 @@ impl Method {
 
         Method {
             name,
+            kind,
+            plain_name,
+            is_default_overload,
             params,
             return_type,
             vtable_offset,
             overload: 1,
+            deprecated: Deprecated::from_attributes(method.attributes()),
+            introduced: Introduced::from_attributes(method.attributes()),
+            is_available: true,
         }
     }
 
+    /// Whether the method is needed for the member, which may name the method itself or an event
+    /// whose `remove_` method is also needed
+    pub fn is_member(&self, member: &str) -> bool {
+        let name = self.gen_name();
+        let name = name.as_str();
+
+        name == member || (self.kind == MethodKind::Remove && name == format!("remove_{}", member))
+    }
+
     pub fn dependencies(&self) -> Vec<winmd::TypeDef> {
         self.return_type
             .iter()
@@ -195,8 +229,14 @@ impl Method {
             }
         };
 
+        let deprecated = self
+            .deprecated
+            .as_ref()
+            .map_or_else(TokenStream::new, |deprecated| deprecated.gen());
+
         match kind {
             InterfaceKind::Default => quote! {
+                #deprecated
                 pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type_tokens> {
                     let this = self;
                     unsafe {
@@ -207,6 +247,7 @@ impl Method {
             InterfaceKind::NonDefault | InterfaceKind::Overrides => {
                 let interface = interface.gen();
                 quote! {
+                    #deprecated
                     pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type_tokens> {
                         let this = &::winrt::Interface::cast::<#interface>(self).unwrap();
                         unsafe {
@@ -218,6 +259,7 @@ impl Method {
             InterfaceKind::Statics | InterfaceKind::Composable => {
                 let interface = interface.gen();
                 quote! {
+                    #deprecated
                     pub fn #method_name<#constraints>(#params) -> ::winrt::Result<#return_type_tokens> {
                         Self::#interface(|this| unsafe { #vcall })
                     }
@@ -226,7 +268,121 @@ impl Method {
         }
     }
 
-    fn gen_name(&self) -> Ident {
+    /// Generates an `on_*` method for an event that registers a closure as the handler and returns a
+    /// guard that calls the matching `remove_*` method when dropped.
+    pub fn gen_revoker(&self, interface: &TypeName, kind: InterfaceKind) -> TokenStream {
+        let delegate = match self.params.first().map(|param| &param.kind) {
+            Some(TypeKind::Delegate(name)) => {
+                Delegate::from_type_name_in(name.clone(), interface.calling_namespace())
+            }
+            _ => return TokenStream::new(),
+        };
+
+        let fn_constraint = delegate.gen_fn_constraint();
+        let delegate = delegate.name.gen();
+        let deprecated = self
+            .deprecated
+            .as_ref()
+            .map_or_else(TokenStream::new, |deprecated| deprecated.gen());
+
+        let event_name = if self.overload > 1 {
+            format!("{}{}", self.name, self.overload)
+        } else {
+            self.name.clone()
+        };
+
+        let on_name = format_ident!("on_{}", event_name);
+
+        match kind {
+            InterfaceKind::Default | InterfaceKind::NonDefault | InterfaceKind::Overrides => {
+                let method_name = self.gen_name();
+                let remove_name = format_ident!("remove_{}", event_name);
+
+                quote! {
+                    #deprecated
+                    pub fn #on_name<#fn_constraint>(&self, handler: F) -> ::winrt::Result<::winrt::EventRevoker<Self>> {
+                        let token = self.#method_name(<#delegate>::new(handler))?;
+                        ::std::result::Result::Ok(::winrt::EventRevoker::new(self, token, |this, token| this.#remove_name(token)))
+                    }
+                }
+            }
+            InterfaceKind::Statics => {
+                // The revoker holds the statics interface itself, whose methods aren't renamed
+                // to avoid collisions with the class's other interfaces.
+                let method_name = format_ident(&self.name);
+                let remove_name = format_ident!("remove_{}", self.name);
+                let interface = interface.gen();
+
+                quote! {
+                    #deprecated
+                    pub fn #on_name<#fn_constraint>(handler: F) -> ::winrt::Result<::winrt::EventRevoker<#interface>> {
+                        let handler = <#delegate>::new(handler);
+                        Self::#interface(|this| {
+                            let token = this.#method_name(&handler)?;
+                            ::std::result::Result::Ok(::winrt::EventRevoker::new(this, token, |this, token| this.#remove_name(token)))
+                        })
+                    }
+                }
+            }
+            InterfaceKind::Composable => TokenStream::new(),
+        }
+    }
+
+    /// Implements an overload dispatch trait for the tuple of this method's arguments. Methods with
+    /// array or output parameters aren't supported.
+    pub fn gen_overload(
+        &self,
+        kind: InterfaceKind,
+        trait_name: &Ident,
+        class_name: &TokenStream,
+    ) -> TokenStream {
+        let method_name = self.gen_name();
+        let constraints = gen_constraint(&self.params);
+
+        let types = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(position, param)| match &param.kind {
+                TypeKind::String
+                | TypeKind::Object
+                | TypeKind::Guid
+                | TypeKind::Class(_)
+                | TypeKind::Interface(_)
+                | TypeKind::Struct(_)
+                | TypeKind::Delegate(_)
+                | TypeKind::Generic(_) => {
+                    let name = squote::format_ident!("T{}__", position);
+                    quote! { #name }
+                }
+                _ => param.kind.gen(),
+            });
+
+        let args = (0..self.params.len() as u32).map(Literal::u32_unsuffixed);
+
+        let return_type = if let Some(return_type) = &self.return_type {
+            return_type.gen_return()
+        } else {
+            quote! { () }
+        };
+
+        let (this_param, call) = if kind == InterfaceKind::Statics {
+            (TokenStream::new(), quote! { #class_name::#method_name })
+        } else {
+            (quote! { this: &#class_name }, quote! { this.#method_name })
+        };
+
+        quote! {
+            impl<#constraints> #trait_name for (#(#types,)*) {
+                type Output = #return_type;
+                fn call(self, #this_param) -> ::winrt::Result<#return_type> {
+                    #call(#(self.#args),*)
+                }
+            }
+        }
+    }
+
+    pub fn gen_name(&self) -> Ident {
         if self.overload > 1 {
             format_ident!("{}{}", &self.name, self.overload)
         } else {
@@ -410,4 +566,48 @@ mod tests {
 
         assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
     }
+
+    #[test]
+    fn test_event_revoker() {
+        let method = method(
+            ("Windows.Foundation.Collections", "IObservableMap`2"),
+            "map_changed",
+        );
+
+        assert!(method.kind == MethodKind::Add);
+
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.Foundation.Collections", "IObservableMap`2"));
+        let name = TypeName::from_type_def(&def, "Windows.Foundation.Collections");
+
+        let tokens = method
+            .gen_revoker(&name, InterfaceKind::Default)
+            .into_string();
+
+        assert!(tokens.contains("pub fn on_map_changed < F : FnMut"));
+        assert!(tokens.contains("EventRevoker < Self >"));
+        assert!(tokens.contains("this . remove_map_changed ( token )"));
+    }
+
+    #[test]
+    fn test_event_revoker_namespace() {
+        let method = method(
+            ("Windows.UI.Xaml.Controls.Primitives", "IButtonBase"),
+            "click",
+        );
+
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def(("Windows.UI.Xaml.Controls.Primitives", "IButtonBase"));
+        let name = TypeName::from_type_def(&def, "Windows.UI.Xaml.Controls.Primitives");
+
+        let tokens = method
+            .gen_revoker(&name, InterfaceKind::Default)
+            .into_string();
+
+        // The closure's parameters are named relative to the interface rather than the delegate.
+        assert!(
+            tokens.contains("& :: std :: option :: Option < super :: super :: RoutedEventArgs >")
+        );
+        assert!(tokens.contains("< super :: super :: RoutedEventHandler > :: new ( handler )"));
+    }
 }
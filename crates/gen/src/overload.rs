@@ -0,0 +1,200 @@
+use crate::*;
+use squote::{format_ident, quote, TokenStream};
+use std::collections::*;
+
+// Generates a dispatcher for each group of overloaded methods so that a single `<name>_with`
+// method accepts the argument tuple of any of the overloads, as in `Class::create_with((a, b))`.
+// WinRT overloads are distinguished by arity so each tuple maps to a single overload. Where a
+// class has several overloads with the same arity only the default overload is included.
+//
+// The plain name is left to the default overload so that the common call needs no tuple, and the
+// dispatcher is always generated since it only calls methods that are generated anyway. The trait
+// that maps each tuple to its overload is an implementation detail and hidden from the docs.
+pub fn gen_overloads(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenStream {
+    let class_name = name.gen();
+    let mut tokens = TokenStream::new();
+
+    for (plain_name, kind, methods) in dispatchers(interfaces) {
+        let camel_name: String = plain_name
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_uppercase().to_string() + chars.as_str()
+                })
+            })
+            .collect();
+
+        let trait_name = format_ident!("{}{}Args", name.name, camel_name);
+        let dispatch_name = format_ident(&format!("{}_with", plain_name));
+
+        let impls = methods
+            .iter()
+            .map(|method| method.gen_overload(kind, &trait_name, &class_name));
+
+        let doc = format!(
+            " Calls the `{}` overload matching the number of arguments in `args`.",
+            plain_name
+        );
+
+        let (this_param, dispatch) = if kind == InterfaceKind::Statics {
+            (
+                TokenStream::new(),
+                quote! {
+                    #[doc = #doc]
+                    pub fn #dispatch_name<A: #trait_name>(args: A) -> ::winrt::Result<A::Output> {
+                        args.call()
+                    }
+                },
+            )
+        } else {
+            (
+                quote! { this: &#class_name },
+                quote! {
+                    #[doc = #doc]
+                    pub fn #dispatch_name<A: #trait_name>(&self, args: A) -> ::winrt::Result<A::Output> {
+                        args.call(self)
+                    }
+                },
+            )
+        };
+
+        tokens.combine(&quote! {
+            #[doc(hidden)]
+            pub trait #trait_name {
+                type Output;
+                fn call(self, #this_param) -> ::winrt::Result<Self::Output>;
+            }
+            #(#impls)*
+            impl #class_name {
+                #dispatch
+            }
+        });
+    }
+
+    tokens
+}
+
+/// Whether the member names the `_with` dispatcher that calls the method
+pub fn is_dispatched(interfaces: &[RequiredInterface], method: &Method, member: &str) -> bool {
+    dispatchers(interfaces)
+        .iter()
+        .any(|(plain_name, _, methods)| {
+            member == format!("{}_with", plain_name)
+                && methods
+                    .iter()
+                    .any(|dispatched| std::ptr::eq(*dispatched, method))
+        })
+}
+
+// The overloads that a dispatcher is generated for keyed by their plain name along with the kind
+// of interface they belong to.
+fn dispatchers(interfaces: &[RequiredInterface]) -> Vec<(&str, InterfaceKind, Vec<&Method>)> {
+    let mut groups = BTreeMap::<&str, Vec<(&Method, InterfaceKind)>>::new();
+
+    for interface in interfaces {
+        if interface.kind == InterfaceKind::Composable {
+            continue;
+        }
+
+        for method in interface
+            .methods
+            .iter()
+            .filter(|method| method.is_available)
+        {
+            groups
+                .entry(&method.plain_name)
+                .or_default()
+                .push((method, interface.kind));
+        }
+    }
+
+    let method_names: BTreeSet<String> = interfaces
+        .iter()
+        .flat_map(|interface| interface.methods.iter())
+        .map(|method| method.gen_name().as_str().to_string())
+        .collect();
+
+    let mut dispatchers = Vec::new();
+
+    for (plain_name, overloads) in groups {
+        if overloads.len() < 2 {
+            continue;
+        }
+
+        let is_static = overloads[0].1 == InterfaceKind::Statics;
+
+        if overloads
+            .iter()
+            .any(|(_, kind)| (*kind == InterfaceKind::Statics) != is_static)
+        {
+            continue;
+        }
+
+        if method_names.contains(&format!("{}_with", plain_name)) {
+            continue;
+        }
+
+        let mut arities = BTreeMap::<usize, Vec<&Method>>::new();
+
+        for (method, _) in &overloads {
+            if method
+                .params
+                .iter()
+                .all(|param| param.input && !param.array)
+            {
+                arities.entry(method.params.len()).or_default().push(method);
+            }
+        }
+
+        let methods: Vec<&Method> = arities
+            .values()
+            .filter_map(|methods| {
+                if methods.len() == 1 {
+                    return Some(methods[0]);
+                }
+
+                let mut defaults = methods.iter().filter(|method| method.is_default_overload);
+
+                match (defaults.next(), defaults.next()) {
+                    (Some(method), None) => Some(*method),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        if methods.len() < 2 {
+            continue;
+        }
+
+        dispatchers.push((plain_name, overloads[0].1, methods));
+    }
+
+    dispatchers
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_overloads() {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def((
+            "Windows.Networking.XboxLive",
+            "XboxLiveEndpointPairTemplate",
+        ));
+
+        let t = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Class(t) => t,
+            _ => panic!("TypeDefinition not a class"),
+        };
+
+        let tokens = gen_overloads(&t.name, &t.interfaces).into_string();
+
+        assert!(tokens.contains("XboxLiveEndpointPairTemplateCreateEndpointPairAsyncArgs"));
+        assert!(tokens.contains("create_endpoint_pair_async_with"));
+        assert!(tokens.contains("XboxLiveEndpointPairTemplateCreateEndpointPairForPortsAsyncArgs"));
+        assert!(tokens.contains("create_endpoint_pair_for_ports_async_with"));
+    }
+}
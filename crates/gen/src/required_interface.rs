@@ -65,6 +65,35 @@ impl RequiredInterface {
         }
     }
 
+    /// Omits any methods introduced after the targeted contract versions from the projection. The
+    /// methods' vtable entries are kept as padding without depending on their parameter types.
+    pub fn limit_contracts(&mut self, contracts: &BTreeMap<String, u32>) {
+        let is_available = Introduced::from_attributes(self.name.def.attributes())
+            .iter()
+            .all(|introduced| introduced.is_available(contracts));
+
+        for method in &mut self.methods {
+            method.is_available = is_available
+                && method
+                    .introduced
+                    .iter()
+                    .all(|introduced| introduced.is_available(contracts));
+        }
+    }
+
+    /// Omits the methods that aren't among the selected members from the projection, keyed by the
+    /// interfaces they're defined in and identified by their vtable offsets. The methods' vtable
+    /// entries are kept as padding without depending on their parameter types.
+    pub fn limit_members(&mut self, used: &BTreeMap<winmd::TypeDef, BTreeSet<u32>>) {
+        if let Some(offsets) = used.get(&self.name.def) {
+            for method in &mut self.methods {
+                if !offsets.contains(&method.vtable_offset) {
+                    method.is_available = false;
+                }
+            }
+        }
+    }
+
     pub fn gen_conversions(&self, from: &TokenStream, constraints: &TokenStream) -> TokenStream {
         match self.kind {
             InterfaceKind::Default => {
@@ -173,28 +202,147 @@ pub fn add_dependencies(
 pub fn gen_method(interfaces: &Vec<RequiredInterface>) -> TokenStream {
     let mut tokens = TokenStream::new();
 
+    let names: BTreeSet<String> = interfaces
+        .iter()
+        .flat_map(|interface| interface.methods.iter())
+        .map(|method| method.gen_name().as_str().to_string())
+        .collect();
+
     for interface in interfaces {
-        for method in &interface.methods {
+        for method in interface
+            .methods
+            .iter()
+            .filter(|method| method.is_available)
+        {
             tokens.combine(&method.gen_method(&interface.name, interface.kind));
+
+            // Event revokers are skipped if they would collide with an overridable `On*` method.
+            if method.kind == MethodKind::Add
+                && !names.contains(&format!("on_{}", method.gen_name().as_str()))
+            {
+                tokens.combine(&method.gen_revoker(&interface.name, interface.kind));
+            }
         }
     }
 
     tokens
 }
 
-pub fn rename_collisions(interfaces: &mut Vec<RequiredInterface>) {
+pub fn rename_collisions(interfaces: &mut [RequiredInterface]) {
     // First sort interfaces to ensure a stable method renaming across versions.
     // TODO: Once fast abi support is added, sorting here will be unnecessary.
     // https://github.com/microsoft/winrt-rs/issues/235
     interfaces.sort();
 
+    let taken: BTreeSet<String> = interfaces
+        .iter()
+        .flat_map(|interface| interface.methods.iter().map(|method| method.name.clone()))
+        .collect();
+
+    // Metadata marks a default overload for each arity so the one with the fewest parameters takes
+    // the plain name shared by the overloads, unless some method already has that name.
+    let mut defaults = BTreeMap::new();
+
+    for (interface_index, interface) in interfaces.iter().enumerate() {
+        for (method_index, method) in interface.methods.iter().enumerate() {
+            if method.is_default_overload && !taken.contains(&method.plain_name) {
+                let candidate = (method.params.len(), interface_index, method_index);
+
+                defaults
+                    .entry(method.plain_name.clone())
+                    .and_modify(|default| {
+                        if candidate < *default {
+                            *default = candidate
+                        }
+                    })
+                    .or_insert(candidate);
+            }
+        }
+    }
+
+    for (plain_name, (_, interface_index, method_index)) in defaults {
+        interfaces[interface_index].methods[method_index].name = plain_name;
+    }
+
+    // Default overloads are numbered first so that they keep their names when they collide with
+    // methods from other interfaces.
     let mut count = BTreeMap::new();
 
-    for interface in interfaces {
-        for method in &mut interface.methods {
-            let count = count.entry(&method.name).or_insert(0);
-            *count += 1;
-            method.overload = *count;
+    for is_default_overload in &[true, false] {
+        for interface in interfaces.iter_mut() {
+            for method in &mut interface.methods {
+                if method.is_default_overload == *is_default_overload {
+                    let count = count.entry(method.name.clone()).or_insert(0);
+                    *count += 1;
+                    method.overload = *count;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn default_interface() -> RequiredInterface {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def((
+            "Windows.Networking.XboxLive",
+            "XboxLiveEndpointPairTemplate",
+        ));
+
+        let t = match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Class(t) => t,
+            _ => panic!("TypeDefinition not a class"),
+        };
+
+        t.interfaces
+            .into_iter()
+            .find(|interface| interface.kind == InterfaceKind::Default)
+            .unwrap()
+    }
+
+    fn find(interface: &RequiredInterface, params: usize) -> &Method {
+        interface
+            .methods
+            .iter()
+            .find(|method| {
+                method.plain_name == "create_endpoint_pair_async" && method.params.len() == params
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_default_overload() {
+        let interface = default_interface();
+
+        // Both overloads are marked as default but only the one with fewer parameters takes the
+        // plain name.
+        assert!(find(&interface, 1).name == "create_endpoint_pair_async");
+        assert!(find(&interface, 2).name == "create_endpoint_pair_with_behaviors_async");
+        assert!(find(&interface, 1).overload == 1);
+    }
+
+    #[test]
+    fn test_collisions() {
+        let mut first = default_interface();
+        let second = default_interface();
+
+        // The first interface sorts first but its colliding method isn't a default overload so the
+        // default overload from the second interface keeps the plain name.
+        for method in &mut first.methods {
+            method.is_default_overload = false;
         }
+
+        let mut interfaces = vec![first, second];
+        rename_collisions(&mut interfaces);
+
+        assert!(find(&interfaces[0], 1).name == "create_endpoint_pair_async");
+        assert!(find(&interfaces[0], 1).overload == 2);
+        assert!(find(&interfaces[1], 1).overload == 1);
+
+        assert!(find(&interfaces[0], 2).overload == 2);
+        assert!(find(&interfaces[1], 2).overload == 1);
     }
 }
@@ -6,6 +6,7 @@ pub struct Struct {
     pub name: TypeName,
     pub fields: Vec<(String, Type)>,
     pub signature: String,
+    pub is_blittable: bool,
 }
 
 impl Struct {
@@ -32,10 +33,13 @@ impl Struct {
             fields.push((field_name, t));
         }
 
+        let is_blittable = fields.iter().all(|(_, t)| t.is_blittable());
+
         Self {
             name,
             fields,
             signature,
+            is_blittable,
         }
     }
 
@@ -49,9 +53,6 @@ impl Struct {
     pub fn gen(&self) -> TokenStream {
         let name = self.name.gen();
 
-        // TODO: if the struct is blittable then don't generate a separate abi type.
-        let abi_ident = format_ident!("{}_abi", self.name.name);
-
         let fields = self.fields.iter().map(|(name, kind)| {
             let name = format_ident(&name);
             let kind = kind.gen_field();
@@ -68,14 +69,6 @@ impl Struct {
             }
         });
 
-        let clones = self.fields.iter().map(|(name, kind)| {
-            let name = format_ident(&name);
-            let clone = kind.gen_clone(&name);
-            quote! {
-                #name: #clone
-            }
-        });
-
         let debug_fields = self.fields.iter().filter_map(|(name, t)| {
             if let TypeKind::Delegate(name) = &t.kind {
                 if !name.def.is_winrt() {
@@ -114,8 +107,6 @@ impl Struct {
             }
         };
 
-        let abi = self.fields.iter().map(|field| field.1.gen_abi());
-
         let runtime_type = if self.signature.is_empty() {
             TokenStream::new()
         } else {
@@ -131,17 +122,60 @@ impl Struct {
 
         let debug_name = self.name.name;
 
+        // A blittable struct is its own ABI type and can simply be copied. Otherwise a separate
+        // ABI type is required along with a field-by-field clone.
+        let (derive, abi, clone) = if self.is_blittable {
+            (
+                quote! { #[derive(::std::clone::Clone, ::std::marker::Copy)] },
+                quote! {
+                    unsafe impl ::winrt::Abi for #name {
+                        type Abi = Self;
+                    }
+                },
+                TokenStream::new(),
+            )
+        } else {
+            let abi_ident = format_ident!("{}_abi", self.name.name);
+            let abi = self.fields.iter().map(|field| field.1.gen_abi());
+
+            let clones = self.fields.iter().map(|(name, kind)| {
+                let name = format_ident(&name);
+                let clone = kind.gen_clone(&name);
+                quote! {
+                    #name: #clone
+                }
+            });
+
+            (
+                TokenStream::new(),
+                quote! {
+                    #[repr(C)]
+                    pub struct #abi_ident(#(#abi),*);
+                    unsafe impl ::winrt::Abi for #name {
+                        type Abi = #abi_ident;
+                    }
+                },
+                quote! {
+                    impl ::std::clone::Clone for #name {
+                        fn clone(&self) -> Self {
+                            Self{ #(#clones),* }
+                        }
+                    }
+                },
+            )
+        };
+
+        let attributes = gen_type_attributes(&self.name.def);
+
         quote! {
+            #attributes
             #[repr(C)]
             #[allow(non_snake_case)]
+            #derive
             pub struct #name {
                 #(#fields),*
             }
-            #[repr(C)]
-            pub struct #abi_ident(#(#abi),*);
-            unsafe impl ::winrt::Abi for #name {
-                type Abi = #abi_ident;
-            }
+            #abi
             impl ::std::default::Default for #name {
                 fn default() -> Self {
                     Self{ #(#defaults),* }
@@ -154,11 +188,7 @@ impl Struct {
                         .finish()
                 }
             }
-            impl ::std::clone::Clone for #name {
-                fn clone(&self) -> Self {
-                    Self{ #(#clones),* }
-                }
-            }
+            #clone
             impl ::std::cmp::PartialEq for #name {
                 fn eq(&self, other: &Self) -> bool {
                     #compare_fields
@@ -169,3 +199,50 @@ impl Struct {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn struct_def((namespace, type_name): (&str, &str)) -> Struct {
+        let reader = &winmd::TypeReader::from_build();
+        let def = reader.resolve_type_def((namespace, type_name));
+
+        match TypeDefinition::from_type_def(&def) {
+            TypeDefinition::Struct(t) => t,
+            _ => panic!("TypeDefinition not a struct"),
+        }
+    }
+
+    #[test]
+    fn test_blittable() {
+        assert!(struct_def(("Windows.Foundation", "Point")).is_blittable);
+        assert!(struct_def(("Windows.Foundation", "Rect")).is_blittable);
+        assert!(struct_def(("Windows.Win32", "RECT")).is_blittable);
+
+        // Blittable because its nested DXGI_RATIONAL field is also blittable.
+        assert!(struct_def(("Windows.Win32", "DXGI_MODE_DESC")).is_blittable);
+
+        // Not blittable because of its callback field.
+        assert!(!struct_def(("Windows.Win32", "CHOOSECOLORW")).is_blittable);
+    }
+
+    #[test]
+    fn test_blittable_token_size() {
+        for name in &[
+            ("Windows.Foundation", "Point"),
+            ("Windows.Foundation", "Rect"),
+            ("Windows.Win32", "DXGI_MODE_DESC"),
+        ] {
+            let mut t = struct_def(*name);
+            let after = t.gen().into_string();
+
+            t.is_blittable = false;
+            let before = t.gen().into_string();
+
+            assert!(!after.contains("_abi"));
+            assert!(before.contains("_abi"));
+            assert!(after.len() < before.len());
+        }
+    }
+}
@@ -136,7 +136,19 @@ impl Type {
         tokens
     }
 
+    /// Returns `true` if the type's Rust and ABI representations are identical and it may be
+    /// copied bit for bit.
+    pub fn is_blittable(&self) -> bool {
+        self.pointers > 0 || self.kind.is_blittable()
+    }
+
     pub fn gen_clone(&self, name: &Ident) -> TokenStream {
+        if let TypeKind::Struct(_) = self.kind {
+            if self.is_blittable() {
+                return quote! { self.#name };
+            }
+        }
+
         match self.kind {
             TypeKind::Bool
             | TypeKind::Char
@@ -396,7 +408,13 @@ impl TypeKind {
                 quote! { <#name as ::winrt::Abi>::Abi }
             }
             Self::Enum(name) => name.gen(),
-            Self::Struct(name) => name.gen_abi(),
+            Self::Struct(name) => {
+                if self.is_blittable() {
+                    name.gen()
+                } else {
+                    name.gen_abi()
+                }
+            }
         }
     }
 
@@ -421,6 +439,34 @@ impl TypeKind {
         }
     }
 
+    /// Returns `true` if the type is a primitive, an enum, or a struct whose fields are all
+    /// transitively blittable.
+    pub fn is_blittable(&self) -> bool {
+        match self {
+            Self::Bool
+            | Self::Char
+            | Self::I8
+            | Self::U8
+            | Self::I16
+            | Self::U16
+            | Self::I32
+            | Self::U32
+            | Self::I64
+            | Self::U64
+            | Self::F32
+            | Self::F64
+            | Self::ISize
+            | Self::USize
+            | Self::ErrorCode
+            | Self::Enum(_) => true,
+            Self::Struct(name) => name
+                .def
+                .fields()
+                .all(|field| Type::from_field(&field, name.namespace).is_blittable()),
+            _ => false,
+        }
+    }
+
     pub fn primitive(&self) -> bool {
         match self {
             Self::Bool
@@ -59,6 +59,48 @@ impl TypeDefinition {
         }
     }
 
+    pub fn limit_contracts(&mut self, contracts: &std::collections::BTreeMap<String, u32>) {
+        for interface in self.interfaces_mut() {
+            interface.limit_contracts(contracts);
+        }
+    }
+
+    pub fn limit_members(
+        &mut self,
+        used: &std::collections::BTreeMap<winmd::TypeDef, std::collections::BTreeSet<u32>>,
+    ) {
+        for interface in self.interfaces_mut() {
+            interface.limit_members(used);
+        }
+    }
+
+    /// Whether the method is needed for the member, which may also name the `_with` dispatcher
+    /// generated for a class's overloads
+    pub fn is_member(&self, method: &Method, member: &str) -> bool {
+        method.is_member(member)
+            || match self {
+                Self::Class(t) => is_dispatched(&t.interfaces, method, member),
+                _ => false,
+            }
+    }
+
+    /// The interfaces whose methods are projected onto a class or interface
+    pub fn interfaces(&self) -> &[RequiredInterface] {
+        match self {
+            Self::Class(t) => &t.interfaces,
+            Self::Interface(t) => &t.interfaces,
+            _ => &[],
+        }
+    }
+
+    fn interfaces_mut(&mut self) -> &mut [RequiredInterface] {
+        match self {
+            Self::Class(t) => &mut t.interfaces,
+            Self::Interface(t) => &mut t.interfaces,
+            _ => &mut [],
+        }
+    }
+
     pub fn name(&self) -> &TypeName {
         match self {
             Self::Class(t) => &t.name,
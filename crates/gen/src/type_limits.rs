@@ -1,9 +1,19 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// The set of relevant namespaces and types
 pub struct TypeLimits<'a> {
     reader: &'a winmd::TypeReader,
     pub inner: BTreeSet<NamespaceTypes>,
+    /// The types excluded from the relevant types unless some other type depends on them
+    pub excluded: BTreeSet<NamespaceTypes>,
+    /// The relevant types that are also available under another name
+    pub renames: BTreeSet<TypeRename>,
+    /// The targeted contract versions keyed by the contract's full name
+    pub contracts: BTreeMap<String, u32>,
+    /// The namespaces provided by other crates' bindings paired with the path to those bindings
+    pub externs: BTreeMap<String, String>,
+    /// The types limited to some of their members, leaving out the rest of their methods
+    pub members: BTreeSet<TypeMembers>,
 }
 
 impl<'a> TypeLimits<'a> {
@@ -11,26 +21,199 @@ impl<'a> TypeLimits<'a> {
         Self {
             reader,
             inner: BTreeSet::new(),
+            excluded: BTreeSet::new(),
+            renames: BTreeSet::new(),
+            contracts: BTreeMap::new(),
+            externs: BTreeMap::new(),
+            members: BTreeSet::new(),
         }
     }
 
-    /// Insert a namespace into the set of relevant namespaces
+    /// Exclude types from the set of relevant types
     ///
-    /// expects the namespace in the form: `parent::namespace::*`s
-    pub fn insert(&mut self, mut limit: NamespaceTypes) -> Result<(), String> {
-        let namespace = match self
+    /// Excluded types are still included if some other relevant type depends on them.
+    pub fn exclude(&mut self, limit: NamespaceTypes) -> Result<(), LimitError> {
+        let limit = self.resolve(limit)?;
+        self.excluded.insert(limit);
+        Ok(())
+    }
+
+    /// Take a namespace and its nested namespaces from another crate's bindings rather than
+    /// generating their types again
+    ///
+    /// expects the path to the module the other crate's bindings are included in, such as
+    /// `my_bindings` or `my_bindings::bindings`
+    pub fn insert_extern(&mut self, namespace: String, path: String) -> Result<(), LimitError> {
+        let lowercase = namespace.to_lowercase();
+        let count = lowercase.split('.').count();
+
+        // The namespace may only contain other namespaces, as `Windows` does, so any namespace
+        // nested in it will do.
+        let found = self.reader.types.keys().find_map(|name| {
+            let prefix = name.split('.').take(count).collect::<Vec<_>>().join(".");
+
+            if prefix.to_lowercase() == lowercase {
+                Some(prefix)
+            } else {
+                None
+            }
+        });
+
+        let namespace = match found {
+            Some(namespace) => namespace,
+            None => self.find_namespace(namespace)?,
+        };
+
+        self.externs.insert(namespace, path);
+        Ok(())
+    }
+
+    /// Whether the type has been excluded from the set of relevant types
+    pub fn is_excluded(&self, namespace: &str, name: &str) -> bool {
+        self.excluded.iter().any(|excluded| {
+            excluded.namespace == namespace
+                && match &excluded.limit {
+                    TypeLimit::All => true,
+                    TypeLimit::Some(types) => types.iter().any(|excluded| excluded == name),
+                }
+        })
+    }
+
+    /// Whether the namespace is taken from another crate's bindings, including namespaces nested in
+    /// one that is
+    pub fn is_extern(&self, namespace: &str) -> bool {
+        self.externs.keys().any(|extern_namespace| {
+            matches!(
+                namespace.strip_prefix(extern_namespace.as_str()),
+                Some(rest) if rest.is_empty() || rest.starts_with('.')
+            )
+        })
+    }
+
+    // Finds the namespace and type names as they're spelled in metadata.
+    fn resolve(&self, limit: NamespaceTypes) -> Result<NamespaceTypes, LimitError> {
+        let namespace = self.find_namespace(limit.namespace)?;
+
+        let limit = match limit.limit {
+            TypeLimit::All => TypeLimit::All,
+            TypeLimit::Some(types) => TypeLimit::Some(
+                types
+                    .into_iter()
+                    .map(|name| self.find_type(&namespace, name))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+
+        Ok(NamespaceTypes { namespace, limit })
+    }
+
+    // Finds the namespace as it's spelled in metadata since namespaces are matched case-insensitively.
+    fn find_namespace(&self, namespace: String) -> Result<String, LimitError> {
+        let lowercase = namespace.to_lowercase();
+
+        if let Some(name) = self
             .reader
             .types
-            .iter()
-            .find(|(name, _)| name.to_lowercase() == limit.namespace.to_lowercase())
+            .keys()
+            .find(|name| name.to_lowercase() == lowercase)
         {
-            Some((n, _)) => n,
-            None => return Err(limit.namespace),
+            return Ok(name.clone());
+        }
+
+        // The first segment that doesn't lead to any known namespace is the one that's wrong.
+        let segments: Vec<&str> = lowercase.split('.').collect();
+        let unknown_segment = (1..=segments.len())
+            .find(|count| {
+                let prefix = segments[..*count].join(".");
+                !self.reader.types.keys().any(|name| {
+                    let name = name.to_lowercase();
+                    name == prefix || name.starts_with(&format!("{}.", prefix))
+                })
+            })
+            .map_or(segments.len() - 1, |count| count - 1);
+
+        let suggestions = closest(
+            &lowercase,
+            self.reader.types.keys().map(|name| name.as_str()),
+            |name| name.to_lowercase(),
+        );
+
+        Err(LimitError::Namespace {
+            namespace,
+            unknown_segment,
+            suggestions,
+        })
+    }
+
+    // Finds the type in metadata, allowing the generic arity to be omitted since Rust type names
+    // don't include it.
+    fn find_type(&self, namespace: &str, name: String) -> Result<String, LimitError> {
+        let types = &self.reader.types[namespace];
+
+        if types.contains_key(&name) {
+            return Ok(name);
+        }
+
+        let generics: Vec<&String> = types
+            .keys()
+            .filter(|candidate| strip_arity(candidate) == name)
+            .collect();
+
+        match generics.as_slice() {
+            [generic] => return Ok((*generic).clone()),
+            [] => {}
+            _ => {
+                return Err(LimitError::Type {
+                    namespace: namespace.to_string(),
+                    name,
+                    hint: Some(format!(
+                        "it matches several generic types: {}",
+                        generics
+                            .iter()
+                            .map(|generic| format!("`{}`", generic))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )),
+                    suggestions: Vec::new(),
+                })
+            }
+        }
+
+        // A type that's written in the wrong namespace is the most likely mistake if its name is
+        // known at all.
+        let elsewhere: Vec<String> = self
+            .reader
+            .types
+            .iter()
+            .filter(|(candidate, types)| {
+                *candidate != namespace
+                    && types.keys().any(|candidate| strip_arity(candidate) == name)
+            })
+            .map(|(candidate, _)| format!("`{}::{}`", namespace_to_path(candidate), name))
+            .take(3)
+            .collect();
+
+        let hint = if elsewhere.is_empty() {
+            None
+        } else {
+            Some(format!("it's defined as {}", elsewhere.join(" and ")))
         };
 
-        limit.namespace = namespace.clone();
-        self.inner.insert(limit);
-        Ok(())
+        let mut suggestions = closest(&name, types.keys().map(|name| name.as_str()), |name| {
+            strip_arity(name).to_string()
+        })
+        .into_iter()
+        .map(|suggestion| strip_arity(&suggestion).to_string())
+        .collect::<Vec<_>>();
+
+        suggestions.dedup();
+
+        Err(LimitError::Type {
+            namespace: namespace.to_string(),
+            name,
+            hint,
+            suggestions,
+        })
     }
 
     pub fn limits(&self) -> impl Iterator<Item = &NamespaceTypes> {
@@ -38,6 +221,157 @@ impl<'a> TypeLimits<'a> {
     }
 }
 
+impl TypeLimits<'static> {
+    /// Insert a namespace into the set of relevant namespaces
+    ///
+    /// expects the namespace in the form: `parent::namespace::*`s
+    ///
+    /// Types selected by name must be available in the targeted contract versions, so any
+    /// contracts should be inserted first.
+    pub fn insert(&mut self, limit: NamespaceTypes) -> Result<(), LimitError> {
+        let limit = self.resolve(limit)?;
+
+        if let TypeLimit::Some(types) = &limit.limit {
+            for name in types {
+                self.check_available(&limit.namespace, name)?;
+            }
+        }
+
+        self.inner.insert(limit);
+        Ok(())
+    }
+
+    /// Insert a type into the set of relevant types that is also available under another name
+    pub fn insert_rename(&mut self, mut rename: TypeRename) -> Result<(), LimitError> {
+        rename.namespace = self.find_namespace(rename.namespace)?;
+        rename.name = self.find_type(&rename.namespace, rename.name)?;
+        self.check_available(&rename.namespace, &rename.name)?;
+
+        self.inner.insert(NamespaceTypes {
+            namespace: rename.namespace.clone(),
+            limit: TypeLimit::Some(vec![rename.name.clone()]),
+        });
+
+        self.renames.insert(rename);
+        Ok(())
+    }
+
+    /// Insert a contract version to target, omitting any APIs introduced in later versions
+    ///
+    /// expects the contract in the form: `UniversalApiContract` or `windows.foundation.UniversalApiContract`
+    /// and the version with the major version in the high word
+    pub fn insert_contract(&mut self, contract: &str, version: u32) -> Result<(), String> {
+        let (namespace, name) = match contract.rfind('.') {
+            Some(pos) => (Some(contract[..pos].to_lowercase()), &contract[pos + 1..]),
+            None => (None, contract),
+        };
+
+        let reader = self.reader;
+
+        let found = reader
+            .types
+            .iter()
+            .filter(|(candidate, _)| {
+                namespace
+                    .iter()
+                    .all(|namespace| *namespace == candidate.to_lowercase())
+            })
+            .find_map(|(candidate, types)| {
+                let row = *types.get(name)?;
+                let def = winmd::TypeDef { reader, row };
+
+                if def.category() == winmd::TypeCategory::Contract {
+                    Some(format!("{}.{}", candidate, name))
+                } else {
+                    None
+                }
+            });
+
+        match found {
+            Some(contract) => {
+                self.contracts.insert(contract, version);
+                Ok(())
+            }
+            None => Err(contract.to_string()),
+        }
+    }
+
+    /// Insert a type into the set of relevant types along with only the members that are used
+    ///
+    /// The methods that aren't members are left out of the type's interfaces, as are the types
+    /// they depend on, unless some other relevant type requires the same interfaces in full.
+    pub fn insert_members(&mut self, mut members: TypeMembers) -> Result<(), LimitError> {
+        members.namespace = self.find_namespace(members.namespace)?;
+        members.name = self.find_type(&members.namespace, members.name)?;
+        self.check_available(&members.namespace, &members.name)?;
+
+        let def = self
+            .reader
+            .resolve_type_def((&members.namespace, &members.name));
+
+        let t = crate::TypeDefinition::from_type_def(&def);
+
+        let is_new = |member: &str| match &t {
+            crate::TypeDefinition::Class(t) => t.default_constructor && member == "new",
+            _ => false,
+        };
+
+        let methods = t
+            .interfaces()
+            .iter()
+            .flat_map(|interface| interface.methods.iter());
+
+        for member in &members.members {
+            if is_new(member) || methods.clone().any(|method| t.is_member(method, member)) {
+                continue;
+            }
+
+            let names: Vec<String> = methods
+                .clone()
+                .map(|method| method.gen_name().as_str().to_string())
+                .collect();
+
+            return Err(LimitError::Member {
+                namespace: members.namespace,
+                name: members.name,
+                member: member.clone(),
+                suggestions: closest(member, names.iter().map(|name| name.as_str()), |name| {
+                    name.to_string()
+                }),
+            });
+        }
+
+        self.members.insert(members);
+        Ok(())
+    }
+
+    // Types that are selected by name are never silently left out, as newer types matched by a `*`
+    // are, so one that was introduced after a targeted contract version is an error.
+    fn check_available(&self, namespace: &str, name: &str) -> Result<(), LimitError> {
+        let def = self.reader.resolve_type_def((namespace, name));
+
+        match crate::Introduced::from_attributes(def.attributes()) {
+            Some(introduced) if !introduced.is_available(&self.contracts) => {
+                Err(LimitError::Unavailable {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    introduced,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A type that is only relevant for some of its members
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TypeMembers {
+    pub namespace: String,
+    pub name: String,
+    /// The names of the methods, properties and events as they're projected such as `read_text`
+    pub members: Vec<String>,
+}
+
 /// A namespace's relevant types
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub struct NamespaceTypes {
@@ -53,3 +387,296 @@ pub enum TypeLimit {
     /// Some types in the namespace
     Some(Vec<String>),
 }
+
+/// The reason a namespace or type couldn't be added to the [`TypeLimits`]
+#[derive(Debug, PartialEq)]
+pub enum LimitError {
+    /// The namespace isn't known
+    Namespace {
+        namespace: String,
+        /// The index of the first `.` separated segment of the namespace that isn't known
+        unknown_segment: usize,
+        /// The closest known namespaces
+        suggestions: Vec<String>,
+    },
+    /// The type isn't known in its namespace
+    Type {
+        namespace: String,
+        name: String,
+        /// An explanation of a likely mistake such as a type in the wrong namespace
+        hint: Option<String>,
+        /// The closest known type names in the namespace
+        suggestions: Vec<String>,
+    },
+    /// The type was introduced after a targeted contract version
+    Unavailable {
+        namespace: String,
+        name: String,
+        introduced: crate::Introduced,
+    },
+    /// The member isn't a method, property or event of the type
+    Member {
+        namespace: String,
+        name: String,
+        member: String,
+        /// The closest known member names of the type
+        suggestions: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suggestions = match self {
+            Self::Namespace {
+                namespace,
+                suggestions,
+                ..
+            } => {
+                write!(f, "'{}' is not a known namespace", namespace)?;
+                suggestions
+                    .iter()
+                    .map(|suggestion| namespace_to_path(suggestion))
+                    .collect()
+            }
+            Self::Type {
+                namespace,
+                name,
+                hint,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "'{}' is not a known type in `{}`",
+                    name,
+                    namespace_to_path(namespace)
+                )?;
+
+                if let Some(hint) = hint {
+                    write!(f, "; {}", hint)?;
+                }
+
+                suggestions.clone()
+            }
+            Self::Unavailable {
+                namespace,
+                name,
+                introduced,
+            } => {
+                write!(
+                    f,
+                    "'{}' in `{}` is not available in the targeted contract versions since it was introduced in {}",
+                    strip_arity(name),
+                    namespace_to_path(namespace),
+                    introduced
+                )?;
+
+                Vec::new()
+            }
+            Self::Member {
+                namespace,
+                name,
+                member,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "'{}' is not a member of `{}::{}`",
+                    member,
+                    namespace_to_path(namespace),
+                    strip_arity(name)
+                )?;
+
+                suggestions.clone()
+            }
+        };
+
+        match suggestions.as_slice() {
+            [] => Ok(()),
+            [suggestion] => write!(f, "; did you mean `{}`?", suggestion),
+            _ => write!(
+                f,
+                "; did you mean one of {}?",
+                suggestions
+                    .iter()
+                    .map(|suggestion| format!("`{}`", suggestion))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+// Formats the namespace as it would be written in `build!` such as `windows::ui::xaml`.
+fn namespace_to_path(namespace: &str) -> String {
+    namespace
+        .split('.')
+        .map(|segment| crate::to_snake(segment, crate::MethodKind::Normal))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn strip_arity(name: &str) -> &str {
+    name.split('`').next().unwrap()
+}
+
+// The candidates within a small edit distance of the name, closest first.
+fn closest<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a str>,
+    key: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let name = name.to_lowercase();
+    let threshold = std::cmp::max(1, name.len() / 3);
+
+    let mut found: Vec<(usize, &str)> = candidates
+        .filter_map(|candidate| {
+            let distance = edit_distance(&name, &key(candidate).to_lowercase());
+
+            if distance <= threshold {
+                Some((distance, candidate))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    found.sort();
+    found
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + if a == *b { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// A type that is also available under another name
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
+pub struct TypeRename {
+    pub namespace: String,
+    /// The type's name in metadata
+    pub name: String,
+    /// The additional name the type is available under
+    pub rename: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("colors", "colors"), 0);
+        assert_eq!(edit_distance("colours", "colors"), 1);
+        assert_eq!(edit_distance("xmal", "xaml"), 2);
+        assert_eq!(edit_distance("", "ui"), 2);
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+
+        match limits.insert(NamespaceTypes {
+            namespace: "windows.ui.xmal".to_owned(),
+            limit: TypeLimit::All,
+        }) {
+            Err(LimitError::Namespace {
+                unknown_segment,
+                suggestions,
+                ..
+            }) => {
+                assert_eq!(unknown_segment, 2);
+                assert!(suggestions.contains(&"Windows.UI.Xaml".to_owned()));
+            }
+            _ => panic!("expected an unknown namespace"),
+        }
+
+        match limits.insert(NamespaceTypes {
+            namespace: "windows.storage".to_owned(),
+            limit: TypeLimit::Some(vec!["Colors".to_owned()]),
+        }) {
+            Err(LimitError::Type { hint, .. }) => {
+                assert_eq!(hint.unwrap(), "it's defined as `windows::ui::Colors`");
+            }
+            _ => panic!("expected an unknown type"),
+        }
+
+        match limits.insert(NamespaceTypes {
+            namespace: "windows.ui".to_owned(),
+            limit: TypeLimit::Some(vec!["Colours".to_owned()]),
+        }) {
+            Err(LimitError::Type { suggestions, .. }) => {
+                assert_eq!(suggestions, ["Colors"]);
+            }
+            _ => panic!("expected an unknown type"),
+        }
+
+        // The generic arity may be omitted.
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.foundation.collections".to_owned(),
+                limit: TypeLimit::Some(vec!["IVector".to_owned()]),
+            })
+            .unwrap();
+
+        assert!(limits
+            .limits()
+            .any(|limit| limit.limit == TypeLimit::Some(vec!["IVector`1".to_owned()])));
+    }
+
+    #[test]
+    fn test_unknown_members() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+
+        let members = |members: &[&str]| TypeMembers {
+            namespace: "windows.networking.xboxlive".to_owned(),
+            name: "XboxLiveEndpointPairTemplate".to_owned(),
+            members: members.iter().map(|member| member.to_string()).collect(),
+        };
+
+        // Events are selected by name along with their `remove_` methods, as are overloads by the
+        // name of their dispatcher.
+        limits
+            .insert_members(members(&[
+                "get_template_by_name",
+                "inbound_endpoint_pair_created",
+                "create_endpoint_pair_async_with",
+            ]))
+            .unwrap();
+
+        // Only overloads that are generated with a dispatcher can be selected by its name.
+        assert!(limits
+            .insert_members(members(&["get_template_by_name_with"]))
+            .is_err());
+
+        let error = limits
+            .insert_members(members(&["get_template_by_nmae"]))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "'get_template_by_nmae' is not a member of \
+             `windows::networking::xbox_live::XboxLiveEndpointPairTemplate`; \
+             did you mean `get_template_by_name`?"
+        );
+    }
+}
@@ -41,6 +41,11 @@ impl TypeName {
         }
     }
 
+    /// The namespace that the type's name is generated relative to
+    pub fn calling_namespace(&self) -> &'static str {
+        self.calling_namespace
+    }
+
     pub fn gen_constraint(&self) -> TokenStream {
         TokenStream::from_iter(self.generics.iter().map(|generic| {
             let generic = generic.gen();
@@ -11,21 +11,132 @@ impl TypeNamespaces {
             let name = crate::to_snake(name, crate::MethodKind::Normal);
             let name = crate::format_ident(&name);
             let tokens = tree.gen().collect::<Vec<_>>();
-
-            let foundation = if tree.include_foundation {
-                quote! { pub use ::winrt::foundation; }
-            } else {
-                TokenStream::new()
-            };
+            let exports = gen_exports(tree);
 
             quote! {
                 // TODO: remove this allowance when https://github.com/microsoft/winrt-rs/issues/212 is fixed
                 #[allow(unused_variables)]
+                #[allow(deprecated)]
                 pub mod #name {
                     #(#tokens)*
-                    #foundation
+                    #exports
                 }
             }
         })
     }
+
+    /// Generates a file per namespace, each declaring its nested namespaces as out-of-line modules
+    ///
+    /// `parent` is the full name of the namespace these namespaces are nested in and `path` is the
+    /// directory its file is in.
+    pub fn gen_files(
+        &self,
+        parent: &str,
+        path: &std::path::Path,
+        features: bool,
+    ) -> (TokenStream, Vec<(std::path::PathBuf, TokenStream)>) {
+        let mut declarations = TokenStream::new();
+        let mut files = Vec::new();
+
+        for (name, tree) in &self.0 {
+            let namespace = if parent.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", parent, name)
+            };
+
+            let snake = crate::to_snake(name, crate::MethodKind::Normal);
+            let path = path.join(&snake);
+            let ident = crate::format_ident(&snake);
+
+            let feature = if features {
+                let feature = namespace_feature(&namespace);
+                quote! { #[cfg(feature = #feature)] }
+            } else {
+                TokenStream::new()
+            };
+
+            declarations.combine(&quote! {
+                #feature
+                // TODO: remove this allowance when https://github.com/microsoft/winrt-rs/issues/212 is fixed
+                #[allow(unused_variables)]
+                #[allow(deprecated)]
+                pub mod #ident;
+            });
+
+            let tokens = tree.types.par_iter().map(|t| t.gen()).collect::<Vec<_>>();
+            let exports = gen_exports(tree);
+            let (nested, nested_files) = tree.namespaces.gen_files(&namespace, &path, features);
+
+            files.push((
+                path.join("mod.rs"),
+                quote! {
+                    #nested
+                    #(#tokens)*
+                    #exports
+                },
+            ));
+
+            files.extend(nested_files);
+        }
+
+        (declarations, files)
+    }
+}
+
+/// The cargo feature that gates a namespace, such as `Windows_UI_Xaml` for `Windows.UI.Xaml`
+pub fn namespace_feature(namespace: &str) -> String {
+    namespace.replace('.', "_")
+}
+
+// The re-exported foundation namespace, any namespaces re-exported from other crates' bindings and
+// any renamed types in a namespace.
+pub(crate) fn gen_exports(tree: &crate::type_tree::TypeTree) -> TokenStream {
+    let foundation = if tree.include_foundation {
+        quote! { pub use ::winrt::foundation; }
+    } else {
+        TokenStream::new()
+    };
+
+    // Generic types are renamed without their generic parameters, as in `pub use IVector as Vector;`.
+    let renames = tree.renames.iter().map(|(name, rename)| {
+        let name = crate::format_ident(name.split('`').next().unwrap());
+        let rename = crate::format_ident(rename);
+        quote! { pub use self::#name as #rename; }
+    });
+
+    let externs = tree.externs.iter().map(|(namespace, path)| {
+        let mut segments: Vec<String> = path
+            .split("::")
+            .map(|segment| segment.to_string())
+            .collect();
+        segments.extend(
+            namespace
+                .split('.')
+                .map(|segment| crate::to_snake(segment, crate::MethodKind::Normal)),
+        );
+
+        let name = crate::format_ident(&segments.pop().unwrap());
+
+        // A path into the current crate starts with `crate` rather than a crate name.
+        let mut path = if segments[0] == "crate" {
+            segments.remove(0);
+            quote! { crate:: }
+        } else {
+            quote! { :: }
+        };
+
+        for segment in &segments {
+            let segment = crate::format_ident(segment);
+            path.combine(&quote! { #segment:: });
+        }
+
+        quote! { pub use #path#name; }
+    });
+
+    quote! {
+        #foundation
+        #(#externs)*
+        #(#renames)*
+    }
 }
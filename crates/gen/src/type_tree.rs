@@ -1,6 +1,6 @@
 use crate::*;
 use rayon::prelude::*;
-use squote::TokenStream;
+use squote::{quote, TokenStream};
 
 /// A namespaced tree of types
 #[derive(Default)]
@@ -8,39 +8,73 @@ pub struct TypeTree {
     pub types: Vec<TypeDefinition>,
     pub namespaces: TypeNamespaces,
     pub include_foundation: bool,
+    /// The names of types in this namespace paired with the other names they're available under
+    pub renames: Vec<(&'static str, String)>,
+    /// The full names of namespaces nested in this namespace that are re-exported from other
+    /// crates' bindings paired with the path to those bindings
+    pub externs: Vec<(String, String)>,
 }
 
 impl TypeTree {
     pub fn from_limits(reader: &'static winmd::TypeReader, limits: &TypeLimits) -> Self {
-        let mut tree = TypeTree::default();
-        let mut set = std::collections::BTreeSet::new();
+        let mut roots = Vec::new();
 
         for limit in limits.limits() {
             match &limit.limit {
                 TypeLimit::All => {
-                    for (_, def) in reader.namespace_types(&limit.namespace) {
+                    for (name, def) in reader.namespace_types(&limit.namespace) {
+                        // Types introduced after a targeted contract version are only included if
+                        // they're required by some other type.
+                        let is_available = Introduced::from_attributes(def.attributes())
+                            .iter()
+                            .all(|introduced| introduced.is_available(&limits.contracts));
+
                         match def.category() {
                             winmd::TypeCategory::Attribute | winmd::TypeCategory::Contract => {}
-                            _ => tree.insert2(reader, &mut set, &def),
+                            _ if !is_available => {}
+                            _ if limits.is_excluded(&limit.namespace, name) => {}
+                            _ => roots.push(def),
                         };
                     }
                 }
                 TypeLimit::Some(types) => {
                     let namespace = &reader.types[&limit.namespace];
-                    for name in types {
-                        tree.insert2(
+                    for name in types
+                        .iter()
+                        .filter(|name| !limits.is_excluded(&limit.namespace, name))
+                    {
+                        roots.push(winmd::TypeDef {
                             reader,
-                            &mut set,
-                            &winmd::TypeDef {
-                                reader,
-                                row: namespace[name],
-                            },
-                        );
+                            row: namespace[name],
+                        });
                     }
                 }
             }
         }
 
+        let used = used_members(reader, limits, &roots);
+        let mut tree = TypeTree::default();
+        let mut set = std::collections::BTreeSet::new();
+
+        for def in &roots {
+            tree.insert2(reader, &mut set, def, limits, &used);
+        }
+
+        for members in &limits.members {
+            let def = reader.resolve_type_def((&members.namespace, &members.name));
+            tree.insert2(reader, &mut set, &def, limits, &used);
+        }
+
+        // A type that has been excluded isn't renamed unless some other type depends on it.
+        for rename in &limits.renames {
+            let def = reader.resolve_type_def((&rename.namespace, &rename.name));
+
+            if set.contains(&def) {
+                let (namespace, name) = def.name();
+                tree.insert_rename(namespace, name, rename.rename.clone());
+            }
+        }
+
         tree
     }
 
@@ -49,12 +83,26 @@ impl TypeTree {
         reader: &winmd::TypeReader,
         set: &mut std::collections::BTreeSet<winmd::TypeDef>,
         def: &winmd::TypeDef,
+        limits: &TypeLimits,
+        used: &std::collections::BTreeMap<winmd::TypeDef, std::collections::BTreeSet<u32>>,
     ) {
         if set.insert(*def) {
-            let t = TypeDefinition::from_type_def(def);
+            let mut t = TypeDefinition::from_type_def(def);
+
+            if !limits.contracts.is_empty() {
+                t.limit_contracts(&limits.contracts);
+            }
 
-            for def in t.dependencies() {
-                self.insert2(reader, set, &def);
+            if !used.is_empty() {
+                t.limit_members(used);
+            }
+
+            // A type taken from another crate's bindings is only kept so that its namespace is
+            // re-exported, while the other crate generates its dependencies.
+            if !limits.is_extern(t.name().namespace) {
+                for def in t.dependencies() {
+                    self.insert2(reader, set, &def, limits, used);
+                }
             }
 
             self.insert(t.name().namespace, t);
@@ -81,6 +129,24 @@ impl TypeTree {
         }
     }
 
+    /// Make a type in the [`TypeTree`] available under another name as well as its own
+    pub fn insert_rename(&mut self, namespace: &'static str, name: &'static str, rename: String) {
+        if let Some(pos) = namespace.find('.') {
+            self.namespaces
+                .0
+                .entry(&namespace[..pos])
+                .or_default()
+                .insert_rename(&namespace[pos + 1..], name, rename);
+        } else {
+            self.namespaces
+                .0
+                .entry(namespace)
+                .or_default()
+                .renames
+                .push((name, rename));
+        }
+    }
+
     pub fn remove(&mut self, namespace: &str) {
         if let Some(pos) = namespace.find('.') {
             if let Some(tree) = self.namespaces.0.get_mut(&namespace[..pos]) {
@@ -91,6 +157,22 @@ impl TypeTree {
         }
     }
 
+    /// Replace a namespace and its nested namespaces with a re-export of the namespace from another
+    /// crate's bindings, if the tree includes it
+    pub fn insert_extern(&mut self, namespace: &str, path: &str) {
+        self.insert_extern_at(namespace, namespace, path);
+    }
+
+    fn insert_extern_at(&mut self, relative: &str, namespace: &str, path: &str) {
+        if let Some(pos) = relative.find('.') {
+            if let Some(tree) = self.namespaces.0.get_mut(&relative[..pos]) {
+                tree.insert_extern_at(&relative[pos + 1..], namespace, path);
+            }
+        } else if self.namespaces.0.remove(relative).is_some() {
+            self.externs.push((namespace.to_string(), path.to_string()));
+        }
+    }
+
     pub fn reexport(&mut self) {
         self.namespaces
             .0
@@ -105,12 +187,156 @@ impl TypeTree {
             .par_iter()
             .map(|t| t.gen())
             .chain(self.namespaces.gen())
+            .chain(rayon::iter::once(gen_exports(self)))
+    }
+
+    /// The indexes of the metadata files that the tree's types are defined in
+    pub fn files(&self) -> std::collections::BTreeSet<u16> {
+        let mut files: std::collections::BTreeSet<u16> = self
+            .types
+            .iter()
+            .map(|t| t.name().def.row.file_index)
+            .collect();
+
+        for tree in self.namespaces.0.values() {
+            files.extend(tree.files());
+        }
+
+        files
+    }
+
+    /// Turn the tree into a file per namespace for code generation
+    ///
+    /// The paths are relative to the directory of the root `mod.rs` file. With `features` each
+    /// namespace is only compiled if its cargo feature, as named by [`namespace_feature`], is enabled.
+    pub fn gen_files(&self, features: bool) -> Vec<(std::path::PathBuf, TokenStream)> {
+        let (declarations, mut files) =
+            self.namespaces
+                .gen_files("", std::path::Path::new(""), features);
+
+        let tokens = self.types.par_iter().map(|t| t.gen()).collect::<Vec<_>>();
+        let exports = gen_exports(self);
+
+        files.insert(
+            0,
+            (
+                std::path::PathBuf::from("mod.rs"),
+                quote! {
+                    #declarations
+                    #(#tokens)*
+                    #exports
+                },
+            ),
+        );
+
+        files
+    }
+
+    /// The cargo features gating each namespace, as a `[features]` table for a Cargo.toml
+    ///
+    /// A namespace's feature enables the features of its parent namespace and of any namespaces
+    /// its types depend on.
+    pub fn cargo_features(&self) -> String {
+        let mut features = std::collections::BTreeMap::new();
+        self.namespace_dependencies("", &mut features);
+
+        let mut table = "[features]\n".to_string();
+
+        for (namespace, dependencies) in &features {
+            let dependencies = dependencies
+                .iter()
+                .filter(|dependency| *dependency != namespace && features.contains_key(*dependency))
+                .map(|dependency| format!("\"{}\"", namespace_feature(dependency)))
+                .collect::<Vec<_>>();
+
+            table.push_str(&format!(
+                "{} = [{}]\n",
+                namespace_feature(namespace),
+                dependencies.join(", ")
+            ));
+        }
+
+        table
+    }
+
+    fn namespace_dependencies(
+        &self,
+        parent: &str,
+        features: &mut std::collections::BTreeMap<String, std::collections::BTreeSet<String>>,
+    ) {
+        for (name, tree) in &self.namespaces.0 {
+            let namespace = if parent.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", parent, name)
+            };
+
+            let mut dependencies: std::collections::BTreeSet<String> = tree
+                .types
+                .iter()
+                .flat_map(|t| t.dependencies())
+                .map(|def| def.name().0.to_string())
+                .collect();
+
+            if !parent.is_empty() {
+                dependencies.insert(parent.to_string());
+            }
+
+            tree.namespace_dependencies(&namespace, features);
+            features.insert(namespace, dependencies);
+        }
     }
 }
 
+// The vtable offsets of the methods that are used in each interface of the types limited to some of
+// their members. An interface that's also required by a type selected in full is left out so that
+// all of its methods remain available, as are the interfaces provided by the `winrt` crate.
+fn used_members(
+    reader: &'static winmd::TypeReader,
+    limits: &TypeLimits,
+    roots: &[winmd::TypeDef],
+) -> std::collections::BTreeMap<winmd::TypeDef, std::collections::BTreeSet<u32>> {
+    let mut used = std::collections::BTreeMap::new();
+
+    for members in &limits.members {
+        let def = reader.resolve_type_def((&members.namespace, &members.name));
+
+        let t = TypeDefinition::from_type_def(&def);
+
+        for interface in t.interfaces() {
+            if FOUNDATION_NAMESPACES.contains(&interface.name.namespace) {
+                continue;
+            }
+
+            let offsets: &mut std::collections::BTreeSet<u32> =
+                used.entry(interface.name.def).or_default();
+
+            for method in &interface.methods {
+                if members
+                    .members
+                    .iter()
+                    .any(|member| t.is_member(method, member))
+                {
+                    offsets.insert(method.vtable_offset);
+                }
+            }
+        }
+    }
+
+    if !used.is_empty() {
+        for def in roots {
+            for interface in TypeDefinition::from_type_def(def).interfaces() {
+                used.remove(&interface.name.def);
+            }
+        }
+    }
+
+    used
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{NamespaceTypes, TypeLimit, TypeLimits, TypeTree};
+    use crate::{NamespaceTypes, TypeLimit, TypeLimits, TypeMembers, TypeRename, TypeTree};
 
     #[test]
     fn test_dependency_inclusion() {
@@ -182,4 +408,175 @@ mod tests {
                 == false
         );
     }
+
+    #[test]
+    fn test_exclusions_and_renames() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.foundation.collections".to_owned(),
+                limit: TypeLimit::All,
+            })
+            .unwrap();
+        limits
+            .exclude(NamespaceTypes {
+                namespace: "windows.foundation.collections".to_owned(),
+                limit: TypeLimit::Some(vec!["PropertySet".to_owned(), "IMap`2".to_owned()]),
+            })
+            .unwrap();
+        limits
+            .insert_rename(TypeRename {
+                namespace: "windows.foundation.collections".to_owned(),
+                name: "IVector`1".to_owned(),
+                rename: "Vector".to_owned(),
+            })
+            .unwrap();
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let collections = &root.namespaces.0["Windows"].namespaces.0["Foundation"]
+            .namespaces
+            .0["Collections"];
+
+        // Excluded types are omitted unless another type depends on them, as IObservableMap
+        // depends on IMap.
+        assert!(!collections
+            .types
+            .iter()
+            .any(|t| t.name().name == "PropertySet"));
+        assert!(collections.types.iter().any(|t| t.name().name == "IMap`2"));
+
+        assert!(collections.renames == [("IVector`1", "Vector".to_owned())]);
+    }
+
+    #[test]
+    fn test_members() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert_members(TypeMembers {
+                namespace: "windows.networking.xboxlive".to_owned(),
+                name: "XboxLiveEndpointPairTemplate".to_owned(),
+                members: vec!["get_template_by_name".to_owned(), "name".to_owned()],
+            })
+            .unwrap();
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let xbox_live = &root.namespaces.0["Windows"].namespaces.0["Networking"]
+            .namespaces
+            .0["XboxLive"];
+
+        let find = |name: &str| xbox_live.types.iter().find(|t| t.name().name == name);
+
+        // The types that only unused methods depend on are left out.
+        assert!(find("XboxLiveEndpointPairTemplate").is_some());
+        assert!(find("XboxLiveSocketKind").is_none());
+        assert!(find("XboxLiveEndpointPair").is_none());
+
+        // The unused methods keep their place in the vtable.
+        let interface = &find("IXboxLiveEndpointPairTemplate").unwrap().interfaces()[0];
+        let used: Vec<&str> = interface
+            .methods
+            .iter()
+            .filter(|method| method.is_available)
+            .map(|method| method.name.as_str())
+            .collect();
+
+        assert_eq!(used, ["name"]);
+        assert_eq!(interface.methods.len(), 13);
+    }
+
+    #[test]
+    fn test_extern_dependencies() {
+        let reader = winmd::TypeReader::from_build();
+        let mut limits = TypeLimits::new(reader);
+        limits
+            .insert(NamespaceTypes {
+                namespace: "windows.devices.gpio".to_owned(),
+                limit: TypeLimit::Some(vec!["GpioController".to_owned()]),
+            })
+            .unwrap();
+        limits
+            .insert_extern(
+                "windows.devices.gpio.provider".to_owned(),
+                "my_bindings".to_owned(),
+            )
+            .unwrap();
+
+        assert!(limits.is_extern("Windows.Devices.Gpio.Provider"));
+        assert!(!limits.is_extern("Windows.Devices.Gpio"));
+
+        let root = TypeTree::from_limits(reader, &limits);
+        let provider = &root.namespaces.0["Windows"].namespaces.0["Devices"]
+            .namespaces
+            .0["Gpio"]
+            .namespaces
+            .0["Provider"];
+
+        // The types that the extern types depend on are left to the other crate.
+        let names: Vec<&str> = provider.types.iter().map(|t| t.name().name).collect();
+        assert_eq!(names, ["IGpioProvider"]);
+    }
+
+    #[test]
+    fn test_gen_files() {
+        let mut root = TypeTree::default();
+        let windows = root.namespaces.0.entry("Windows").or_default();
+        let ui = windows.namespaces.0.entry("UI").or_default();
+        ui.namespaces.0.entry("Xaml").or_default();
+
+        let files = root.gen_files(true);
+        let paths: Vec<_> = files.iter().map(|(path, _)| path.clone()).collect();
+
+        assert_eq!(
+            paths,
+            [
+                std::path::Path::new("mod.rs"),
+                std::path::Path::new("windows/mod.rs"),
+                std::path::Path::new("windows/ui/mod.rs"),
+                std::path::Path::new("windows/ui/xaml/mod.rs"),
+            ]
+        );
+
+        let ui = files[2].1.as_str();
+        assert!(ui.contains("pub mod xaml ;"));
+        assert!(ui.contains("\"Windows_UI_Xaml\""));
+
+        assert_eq!(
+            root.cargo_features(),
+            "[features]\nWindows = []\nWindows_UI = [\"Windows\"]\nWindows_UI_Xaml = [\"Windows_UI\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_externs() {
+        let mut root = TypeTree::default();
+        let windows = root.namespaces.0.entry("Windows").or_default();
+        let ui = windows.namespaces.0.entry("UI").or_default();
+        ui.namespaces.0.entry("Xaml").or_default();
+        windows.namespaces.0.entry("Storage").or_default();
+
+        // Namespaces the tree doesn't include are left alone.
+        root.insert_extern("Windows.Devices", "my_bindings");
+        root.insert_extern("Windows.UI", "my_bindings::bindings");
+
+        let windows = &root.namespaces.0["Windows"];
+        assert!(windows.namespaces.0.keys().eq(&["Storage"]));
+        assert!(windows.externs == [("Windows.UI".to_owned(), "my_bindings::bindings".to_owned())]);
+
+        let files = root.gen_files(false);
+        assert!(crate::format(files[1].1.as_str())
+            .contains("pub use ::my_bindings::bindings::windows::ui;"));
+
+        root.insert_extern("Windows", "my_bindings");
+        assert!(root.namespaces.0.is_empty());
+        assert!(crate::format(root.gen_files(false)[0].1.as_str())
+            .contains("pub use ::my_bindings::windows;"));
+
+        let mut root = TypeTree::default();
+        root.namespaces.0.entry("Windows").or_default();
+        root.insert_extern("Windows", "crate::bindings");
+        assert!(crate::format(root.gen_files(false)[0].1.as_str())
+            .contains("pub use crate::bindings::windows;"));
+    }
 }
@@ -0,0 +1,138 @@
+use quote::quote;
+use winrt_gen::DeployDeclaration;
+
+/// Generates the build script code that deploys the DLLs in the workspace's `.windows` directory
+/// to the directory the package's binaries are written to
+pub fn gen(deploy: &DeployDeclaration) -> proc_macro2::TokenStream {
+    if !deploy.enabled {
+        return quote! {};
+    }
+
+    let workspace_windows_dir = winmd::workspace_windows_dir();
+    let workspace_windows_dir = workspace_windows_dir
+        .to_str()
+        .expect("Invalid workspace windows dir");
+
+    let names = deploy.files.iter().map(|file| file.value());
+
+    let deploy_file = if deploy.symlink {
+        quote! {
+            #[cfg(windows)]
+            let result = ::std::os::windows::fs::symlink_file(&file, &destination);
+            #[cfg(not(windows))]
+            let result = ::std::os::unix::fs::symlink(&file, &destination);
+
+            result.unwrap_or_else(|error| {
+                panic!("Failed to link {:?} to {:?}: {}", destination, file, error)
+            });
+        }
+    } else {
+        quote! {
+            ::std::fs::copy(&file, &destination).unwrap_or_else(|error| {
+                panic!("Failed to copy {:?} to {:?}: {}", file, destination, error)
+            });
+        }
+    };
+
+    let report = if deploy.report {
+        quote! { println!("cargo:warning=Deployed {}", destination.display()); }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        let windows_dir = ::std::path::PathBuf::from(#workspace_windows_dir);
+
+        if windows_dir.exists() {
+            println!("cargo:rerun-if-changed={}", #workspace_windows_dir);
+        }
+
+        // The architecture is only resolved when there's something to deploy so that targets
+        // without a deployment directory build regardless of their architecture.
+        let source = if windows_dir.is_dir() {
+            let target = ::std::env::var("TARGET").expect("No `TARGET` env variable set");
+
+            let architecture = match ::std::env::var("CARGO_CFG_TARGET_ARCH")
+                .expect("No `CARGO_CFG_TARGET_ARCH` env variable set")
+                .as_str()
+            {
+                "x86_64" => "x64",
+                "x86" => "x86",
+                "arm" => "arm",
+                "aarch64" => "arm64",
+                unexpected => panic!("Unexpected target architecture `{}`", unexpected),
+            };
+
+            // A directory named after the target triple takes precedence over one named after the
+            // architecture so that, for example, gnu and msvc targets can deploy different DLLs.
+            [target.as_str(), architecture]
+                .iter()
+                .map(|name| windows_dir.join(name))
+                .find(|dir| dir.is_dir())
+        } else {
+            ::std::option::Option::None
+        };
+
+        let names: &[&str] = &[#(#names),*];
+
+        let files: ::std::vec::Vec<::std::path::PathBuf> = if !names.is_empty() {
+            names
+                .iter()
+                .map(|name| {
+                    source
+                        .as_ref()
+                        .map(|source| source.join(name))
+                        .filter(|file| file.is_file())
+                        .unwrap_or_else(|| {
+                            panic!("Could not find `{}` to deploy in {:?}", name, windows_dir)
+                        })
+                })
+                .collect()
+        } else if let ::std::option::Option::Some(source) = &source {
+            let mut files: ::std::vec::Vec<_> = ::std::fs::read_dir(source)
+                .unwrap_or_else(|error| panic!("Failed to read {:?}: {}", source, error))
+                .map(|entry| entry.expect("Failed to read directory entry").path())
+                .filter(|file| file.is_file())
+                .collect();
+
+            files.sort();
+            files
+        } else {
+            ::std::vec::Vec::new()
+        };
+
+        // `OUT_DIR` is `<target dir>/[<triple>/]<profile>/build/<package>/out` so the directory the
+        // binaries are written to is found relative to it, respecting `CARGO_TARGET_DIR` and `--target`.
+        let out_dir = ::std::path::PathBuf::from(
+            ::std::env::var("OUT_DIR").expect("No `OUT_DIR` env variable set"),
+        );
+
+        let binaries_dir = out_dir
+            .ancestors()
+            .nth(3)
+            .expect("Unexpected `OUT_DIR` layout")
+            .to_path_buf();
+
+        // The deployed files are listed in `OUT_DIR` for tools and troubleshooting.
+        let mut deployed = ::std::string::String::new();
+
+        for file in files {
+            let destination = binaries_dir.join(file.file_name().expect("Invalid file name"));
+
+            // An earlier deployment is removed first since copying to a symlink would overwrite
+            // the file it links to.
+            if destination.symlink_metadata().is_ok() {
+                ::std::fs::remove_file(&destination).unwrap_or_else(|error| {
+                    panic!("Failed to replace {:?}: {}", destination, error)
+                });
+            }
+
+            #deploy_file
+            #report
+            deployed.push_str(&format!("{}\n", destination.display()));
+        }
+
+        ::std::fs::write(out_dir.join("deployed.txt"), deployed)
+            .expect("Could not write the list of deployed files");
+    }
+}
@@ -50,7 +50,7 @@ fn use_tree_to_types(
                 }
             }
             ImplementTree::Name(name) => {
-                let namespace = crate::namespace_literal_to_rough_namespace(&current.clone());
+                let namespace = namespace_literal_to_rough_namespace(&current.clone());
 
                 let namespace_types = match reader
                     .types
@@ -163,8 +163,10 @@ pub fn gen(
                 let method_ident = format_ident!("{}", method.name);
                 let vcall_ident = format_ident!("abi{}_{}", interface_count, method.vtable_offset);
 
+                // The cast also fills the padding the bindings have in place of any methods that
+                // aren't available to them.
                 vtable_ptrs.combine(&quote! {
-                    Self::#vcall_ident,
+                    Self::#vcall_ident as _,
                 });
 
                 let signature = method.gen_abi();
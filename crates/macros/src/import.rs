@@ -0,0 +1,85 @@
+use quote::quote;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use winrt_gen::{BuildLimits, Generator};
+
+thread_local! {
+    // The bindings already generated while compiling the current crate keyed by the macro's input
+    // and paired with the metadata files they depend on.
+    static BINDINGS: RefCell<BTreeMap<String, (String, Vec<PathBuf>)>> = const { RefCell::new(BTreeMap::new()) };
+
+    // The metadata files that the current crate already depends on.
+    static TRACKED: RefCell<BTreeSet<PathBuf>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+pub fn gen(stream: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let key = stream.to_string();
+
+    let (code, files) = match BINDINGS.with(|bindings| bindings.borrow().get(&key).cloned()) {
+        Some(bindings) => bindings,
+        None => match gen_bindings(stream) {
+            Ok(bindings) => {
+                BINDINGS.with(|cache| cache.borrow_mut().insert(key, bindings.clone()));
+                bindings
+            }
+            Err(error) => return error.to_compile_error().into(),
+        },
+    };
+
+    // Including the metadata files has the crate recompiled, and the bindings generated again, when
+    // they change. Each file only needs to be included once per crate.
+    let files: Vec<String> = TRACKED.with(|tracked| {
+        let mut tracked = tracked.borrow_mut();
+
+        files
+            .into_iter()
+            .filter(|file| tracked.insert(file.clone()))
+            .map(|file| {
+                // `include_bytes!` resolves relative paths against the invoking source file.
+                std::fs::canonicalize(&file)
+                    .unwrap_or(file)
+                    .to_str()
+                    .expect("Invalid winmd path")
+                    .to_string()
+            })
+            .collect()
+    });
+
+    let bindings: proc_macro2::TokenStream =
+        code.parse().expect("Generated code could not be parsed");
+
+    let tokens = quote! {
+        const _: ::std::option::Option<&str> = ::std::option_env!("WINRT_METADATA_PATH");
+        #(const _: &[u8] = ::std::include_bytes!(#files);)*
+        #bindings
+    };
+
+    tokens.into()
+}
+
+fn gen_bindings(stream: proc_macro::TokenStream) -> Result<(String, Vec<PathBuf>), syn::Error> {
+    let build: BuildLimits = syn::parse(stream)?;
+    let reader = build.reader()?;
+    let limits = build.to_limits(reader)?;
+
+    let (code, fingerprint) = Generator::new(reader)
+        .limits(limits)
+        .gen_cached(cache_dir());
+    Ok((code, fingerprint.files))
+}
+
+// The bindings are cached in the target directory so that they're only generated again when the
+// types or metadata change rather than every time the crate is compiled.
+fn cache_dir() -> PathBuf {
+    let mut dir = winmd::workspace_windows_dir();
+    dir.pop();
+
+    match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(target) => dir.push(target),
+        None => dir.push("target"),
+    }
+
+    dir.push("winrt-import");
+    dir
+}
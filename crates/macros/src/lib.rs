@@ -1,9 +1,10 @@
-mod build_limits;
+mod deploy;
 mod implement;
 mod implement_tree;
+mod import;
 
-use build_limits::*;
 use implement_tree::*;
+use winrt_gen::{namespace_literal_to_rough_namespace, BuildLimits, Generator};
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -32,97 +33,219 @@ use syn::parse_macro_input;
 ///     microsoft::ai::machine_learning::*
 /// );
 /// ```
+///
+/// # Contract versions
+/// A `contracts` block limits the generated APIs to those available in the given API contract
+/// versions. Methods introduced in later versions are omitted so that calling them is a compile
+/// error, along with the types that only they depend on, as are newer types matched by a `*`
+/// unless another type depends on them. Selecting a newer type by name is an error. Contracts
+/// that aren't listed are not limited.
+///
+/// ```rust,ignore
+/// build!(
+///     contracts { UniversalApiContract = 10, windows::foundation::FoundationContract = 3.0 }
+///     windows::ui::*
+/// );
+/// ```
+///
+/// # Sharing types between crates
+/// Types generated by different crates are distinct even when they have the same name, so
+/// `extern` takes a namespace from another crate's bindings rather than generating it again. The
+/// namespace and any namespaces nested in it are then re-exported from the given path, where the
+/// other crate's bindings are included, so the other crate must generate every type needed from
+/// them. The path starts with a crate name, or with `crate` for bindings in the same crate. Here the `windows::ui` types that `windows::application_model::core` depends on are used
+/// from `my_bindings`:
+///
+/// ```rust,ignore
+/// build!(
+///     extern windows::ui from my_bindings
+///     windows::application_model::core::*
+/// );
+/// ```
+///
+/// # Metadata
+/// The types are found in the metadata given by the `WINRT_METADATA_PATH` environment variable, a
+/// list of winmd files and directories of winmd files separated as `PATH` is. Otherwise they're
+/// found in the `metadata` array of the package's `[package.metadata.winrt]` table, then in the
+/// workspace's `.windows/winmd` directory and finally in the system's metadata.
+///
+/// ```toml
+/// [package.metadata.winrt]
+/// metadata = ["winmd", "../component/Component.winmd"]
+/// ```
+///
+/// A `metadata` block instead names the files and directories for this `build!` alone. Paths in
+/// `[package.metadata.winrt]` and `metadata` blocks are relative to the package's directory. When
+/// a namespace can't be found the error lists where the metadata was loaded from.
+///
+/// ```rust,ignore
+/// build!(
+///     metadata { "winmd", "../component/Component.winmd" }
+///     component::*
+/// );
+/// ```
+///
+/// # Deployment
+/// The generated `build` function also deploys the files in the workspace's `.windows/<arch>`
+/// directory, such as `.windows/x64`, to the directory the package's binaries are written to so
+/// that any DLLs they depend on can be found. A `.windows/<target triple>` directory takes
+/// precedence when it exists. The deployed files are listed in `deployed.txt` in `OUT_DIR`.
+///
+/// A `deploy` block configures this step: `files` limits it to the given file names, `symlink`
+/// links rather than copies the files, `report` lists each deployed file as a cargo warning and
+/// `enabled = false` turns deployment off.
+///
+/// ```rust,ignore
+/// build!(
+///     deploy { files = ["Component.dll"], symlink = true, report = true }
+///     component::*
+/// );
+/// ```
+///
+/// # Nested groups, renames and exclusions
+/// Paths may be nested in groups and types may be renamed with `as`, as with Rust `use` paths. A
+/// renamed type is available under both names since other types may refer to it by its own name.
+/// A path with a `!` in front of it excludes the types it matches, although an excluded type is
+/// still generated if some other type depends on it.
+///
+/// ```rust,ignore
+/// build!(
+///     windows::{ui::{Colors as UiColors, text::FontWeights}, storage::*}
+///     windows::ui::xaml::media::media3d::*
+///     !windows::ui::xaml::media::media3d::Matrix3DHelper
+/// );
+/// ```
+///
+/// # Minimal bindings
+/// A type's path may be followed by the members that are used, named as they're projected. The
+/// rest of the methods of the type's interfaces are left out, keeping only their place in the
+/// vtable, and so are the types that only they depend on. An event is named without its `add_` or
+/// `remove_` prefix and the overloads behind a `_with` dispatcher by the dispatcher's name. An
+/// interface that's also required by a type selected in full keeps all of its methods.
+///
+/// ```rust,ignore
+/// build!(
+///     windows::storage::StorageFile::{get_file_from_path_async, name}
+///     windows::storage::FileIO::read_text_async
+/// );
+/// ```
+///
+/// # Overloads
+/// An overloaded method's default overload takes the plain name while the others keep the names
+/// given by their metadata. A class's overloads are also available through a `_with` method that
+/// takes the arguments of any of them as a tuple, such as
+/// `template.create_endpoint_pair_async_with((address, behaviors))`.
 #[proc_macro]
 pub fn build(stream: TokenStream) -> TokenStream {
     let build = parse_macro_input!(stream as BuildLimits);
 
-    let tokens = match build.to_tokens_string() {
-        Ok(t) => t,
-        Err(t) => return t.into(),
+    let reader = match build.reader() {
+        Ok(reader) => reader,
+        Err(error) => return error.to_compile_error().into(),
     };
 
-    let workspace_windows_dir = winmd::workspace_windows_dir();
+    let deploy = deploy::gen(&build.deploy);
 
-    let mut source = workspace_windows_dir.clone();
-    source.push(ARCHITECTURE);
-    let source = source.to_str().expect("Invalid workspace architecture dir");
+    let (tokens, fingerprint) = match build.to_limits(reader) {
+        Ok(limits) => Generator::new(reader).limits(limits).gen_with_fingerprint(),
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let mut destination = workspace_windows_dir.clone();
-    destination.pop();
-    destination.push("target");
-    let destination = destination.to_str().expect("Invalid workspace target dir");
+    // The generated code is hashed along with the fingerprint since changes to the generator
+    // that keep its version still change the bindings.
+    let mut hash = sha1::Sha1::new();
+    hash.update(fingerprint.hash.as_bytes());
+    hash.update(tokens.as_bytes());
+    let hash = hash.digest().to_string();
 
-    let workspace_windows_dir = workspace_windows_dir
-        .to_str()
-        .expect("Invalid workspace windows dir");
+    let files: Vec<String> = fingerprint
+        .files
+        .iter()
+        .map(|file| {
+            std::fs::canonicalize(file)
+                .unwrap_or_else(|_| file.clone())
+                .to_str()
+                .expect("Invalid winmd path")
+                .to_string()
+        })
+        .collect();
 
     let tokens = quote! {
         {
-            // The following must be injected into the token stream because the `OUT_DIR` and `PROFILE`
-            // environment variables are only set when the build script run and not when it is being compiled.
+            // The following must be injected into the token stream because the `OUT_DIR`, `PROFILE` and
+            // target environment variables are only set when the build script run and not when it is being
+            // compiled. The build script is compiled for the host so `cfg` can't be used to find the target.
 
             use ::std::io::Write;
             let mut path = ::std::path::PathBuf::from(
                 ::std::env::var("OUT_DIR").expect("No `OUT_DIR` env variable set"),
             );
 
-            path.push("windows.rs");
-            let mut file = ::std::fs::File::create(&path).expect("Failed to create windows.rs");
-            file.write_all(#tokens.as_bytes()).expect("Could not write generated code to output file");
-
-            let mut cmd = ::std::process::Command::new("rustfmt");
-            cmd.arg(&path);
-            let _ = cmd.output();
-
-            fn copy(source: &::std::path::PathBuf, destination: &mut ::std::path::PathBuf) {
-                if let ::std::result::Result::Ok(files) = ::std::fs::read_dir(source) {
-                    for file in files.filter_map(|file| file.ok())  {
-                        if let ::std::result::Result::Ok(file_type) = file.file_type() {
-                            if file_type.is_file() {
-                                let path = file.path();
-                                if let ::std::option::Option::Some(filename) = path.file_name() {
-                                    destination.push(filename);
-                                    let _ = ::std::fs::copy(path, &destination);
-                                    destination.pop();
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            // Reading the variable here has the build script recompiled, and the bindings
+            // generated again, when it changes.
+            let _ = ::std::option_env!("WINRT_METADATA_PATH");
+            println!("cargo:rerun-if-env-changed=WINRT_METADATA_PATH");
+
+            // The bindings are generated when the build script is compiled so including the metadata
+            // has the build script recompiled when any of the files change, rather than writing out
+            // the bindings it was compiled with again.
+            #(const _: &[u8] = ::std::include_bytes!(#files);)*
 
-            fn copy_to_profile(source: &::std::path::PathBuf, destination: &::std::path::PathBuf, profile: &str) {
-                if let ::std::result::Result::Ok(files) = ::std::fs::read_dir(destination) {
-                    for file in files.filter_map(|file| file.ok())  {
-                        if let ::std::result::Result::Ok(file_type) = file.file_type() {
-                            if file_type.is_dir() {
-                                let mut path = file.path();
-                                if let ::std::option::Option::Some(filename) = path.file_name() {
-                                    if filename == profile {
-                                        copy(source, &mut path);
-                                    } else {
-                                        copy_to_profile(source, &path, profile);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            let files: &[&str] = &[#(#files),*];
+
+            for file in files {
+                println!("cargo:rerun-if-changed={}", file);
             }
 
-            if ::std::path::PathBuf::from(#workspace_windows_dir).exists() {
-                println!("cargo:rerun-if-changed={}", #workspace_windows_dir);
-                let source = ::std::path::PathBuf::from(#source);
-                let destination = ::std::path::PathBuf::from(#destination);
-                let profile = ::std::env::var("PROFILE").expect("No `PROFILE` env variable set");
-                copy_to_profile(&source, &destination, &profile);
+            // The hash of the inputs is stored next to the output so that the bindings are only
+            // written again if they've changed.
+            let mut hash_path = path.clone();
+            hash_path.push("windows.sha1");
+            path.push("windows.rs");
+
+            let is_unchanged = path.exists()
+                && ::std::fs::read_to_string(&hash_path).map_or(false, |hash| hash == #hash);
+
+            if !is_unchanged {
+                let mut file = ::std::fs::File::create(&path).expect("Failed to create windows.rs");
+                file.write_all(#tokens.as_bytes()).expect("Could not write generated code to output file");
+
+                ::std::fs::write(&hash_path, #hash).expect("Could not write the hash of the generated code");
             }
+
+            #deploy
         }
     };
 
     tokens.into()
 }
 
+/// A macro for generating WinRT modules in place without a build script.
+///
+/// This macro takes the same syntax as [`build!`] but expands to the bindings themselves rather
+/// than a function that writes them to a file, which is simpler for small tools and tests:
+///
+/// ```rust,ignore
+/// winrt::import!(
+///     windows::data::xml::dom::*
+/// );
+///
+/// fn main() -> winrt::Result<()> {
+///     let doc = windows::data::xml::dom::XmlDocument::new()?;
+///     doc.load_xml("<html>hello world</html>")
+/// }
+/// ```
+///
+/// Each use generates its own types, so types imported in different modules are distinct even when
+/// they have the same name. Import them once and `use` them from elsewhere instead. The bindings
+/// are cached in the `winrt-import` directory of the target directory so that they're only
+/// generated again when the types or the metadata change. Since there is no build script, a
+/// `deploy` block has no effect.
+#[proc_macro]
+pub fn import(stream: TokenStream) -> TokenStream {
+    import::gen(stream)
+}
+
 /// Rust structs can use the `implement` macro to implement entire WinRT classes or
 /// any combination of existing COM and WinRT interfaces.
 ///
@@ -133,24 +256,3 @@ pub fn build(stream: TokenStream) -> TokenStream {
 pub fn implement(attribute: TokenStream, input: TokenStream) -> TokenStream {
     implement::gen(attribute, input)
 }
-
-// Snake <-> camel casing is lossy so we go for character but not case conversion
-// and deal with casing once we have an index of namespaces to compare against.
-pub(crate) fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
-    let mut result = String::with_capacity(namespace.len());
-    for c in namespace.chars() {
-        if c != '"' && c != '_' {
-            result.extend(c.to_lowercase());
-        }
-    }
-    result
-}
-
-#[cfg(target_arch = "x86_64")]
-const ARCHITECTURE: &str = "x64";
-#[cfg(target_arch = "x86")]
-const ARCHITECTURE: &str = "x86";
-#[cfg(target_arch = "arm")]
-const ARCHITECTURE: &str = "arm";
-#[cfg(target_arch = "aarch64")]
-const ARCHITECTURE: &str = "arm64";
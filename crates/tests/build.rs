@@ -26,10 +26,18 @@ fn main() {
         // Test for https://github.com/microsoft/winrt-rs/issues/361
         windows::ui::xaml::{IApplicationOverrides, IApplicationOverrides2}
 
+        // Renames, nested groups and exclusions
+        windows::{ui::{Colors as NamedColors, text::FontWeights}, storage::streams::Buffer}
+        windows::ui::xaml::media::media3d::*
+        !windows::ui::xaml::media::media3d::Matrix3DHelper
+
+        // Only the members that are used
+        windows::storage::StorageFile::{get_file_from_path_async, name}
+
         // Used to test Win32 support
         windows::win32::{
             ACCESS_MODE, CHOOSECOLORW, DXGI_ADAPTER_FLAG, DXGI_FORMAT, DXGI_MODE_DESC, DXGI_MODE_SCALING,
-            DXGI_MODE_SCANLINE_ORDER, DXGI_RATIONAL, RECT,
+            DXGI_MODE_SCANLINE_ORDER, DXGI_RATIONAL, RECT, WNDENUMPROC, WNDPROC,
         }
     );
 }
@@ -53,3 +53,261 @@ async fn async_await() -> winrt::Result<()> {
 fn test_async_await() -> winrt::Result<()> {
     futures::executor::block_on(async_await())
 }
+
+async fn async_progress() -> winrt::Result<()> {
+    use futures::StreamExt;
+    use tests::windows::storage::streams::*;
+
+    let writer = DataWriter::new()?;
+    writer.write_bytes(&[1, 2, 3])?;
+    let buffer = writer.detach_buffer()?;
+
+    let stream = InMemoryRandomAccessStream::new()?;
+    let (written, progress) = stream.write_async(buffer)?.with_progress()?;
+
+    // The progress stream ends once the operation completes, so both can be awaited together.
+    let (written, progress) = futures::join!(written, progress.collect::<Vec<u32>>());
+    assert!(written? == 3);
+    assert!(progress.iter().all(|bytes| *bytes <= 3));
+
+    Ok(())
+}
+
+#[test]
+fn test_async_progress() -> winrt::Result<()> {
+    futures::executor::block_on(async_progress())
+}
+
+mod windows {
+    pub use winrt::foundation;
+}
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use windows::foundation::{AsyncActionCompletedHandler, AsyncStatus, IAsyncAction};
+
+#[winrt::implement(windows::foundation::{IAsyncAction, IAsyncInfo})]
+struct Action(Arc<Mutex<ActionState>>);
+
+struct ActionState {
+    status: AsyncStatus,
+    error_code: winrt::ErrorCode,
+    handler: Option<AsyncActionCompletedHandler>,
+    registrations: u32,
+    canceled: bool,
+}
+
+impl Action {
+    fn set_completed(&self, handler: &Option<AsyncActionCompletedHandler>) -> winrt::Result<()> {
+        let mut state = self.0.lock().unwrap();
+        state.registrations += 1;
+
+        if state.handler.is_some() {
+            // E_ILLEGAL_DELEGATE_ASSIGNMENT
+            return Err(winrt::ErrorCode(0x8000_0018).into());
+        }
+
+        state.handler = handler.clone();
+        Ok(())
+    }
+
+    fn completed(&self) -> winrt::Result<Option<AsyncActionCompletedHandler>> {
+        Ok(self.0.lock().unwrap().handler.clone())
+    }
+
+    fn get_results(&self) -> winrt::Result<()> {
+        Ok(())
+    }
+
+    fn id(&self) -> winrt::Result<u32> {
+        Ok(1)
+    }
+
+    fn status(&self) -> winrt::Result<AsyncStatus> {
+        Ok(self.0.lock().unwrap().status)
+    }
+
+    fn error_code(&self) -> winrt::Result<winrt::ErrorCode> {
+        Ok(self.0.lock().unwrap().error_code)
+    }
+
+    fn cancel(&self) -> winrt::Result<()> {
+        self.0.lock().unwrap().canceled = true;
+        Ok(())
+    }
+
+    fn close(&self) -> winrt::Result<()> {
+        Ok(())
+    }
+}
+
+fn new_action() -> (IAsyncAction, Arc<Mutex<ActionState>>) {
+    let state = Arc::new(Mutex::new(ActionState {
+        status: AsyncStatus::Started,
+        error_code: winrt::ErrorCode(0),
+        handler: None,
+        registrations: 0,
+        canceled: false,
+    }));
+
+    (Action(state.clone()).into(), state)
+}
+
+fn finish(
+    action: &IAsyncAction,
+    state: &Mutex<ActionState>,
+    status: AsyncStatus,
+    error_code: winrt::ErrorCode,
+) -> winrt::Result<()> {
+    let handler = {
+        let mut state = state.lock().unwrap();
+        state.status = status;
+        state.error_code = error_code;
+        state.handler.clone()
+    };
+
+    match handler {
+        Some(handler) => handler.invoke(action, status),
+        None => Ok(()),
+    }
+}
+
+struct Flag(AtomicBool);
+
+impl futures::task::ArcWake for Flag {
+    fn wake_by_ref(flag: &Arc<Self>) {
+        flag.0.store(true, Ordering::SeqCst);
+    }
+}
+
+fn poll<F: Future + Unpin>(future: &mut F, flag: &Arc<Flag>) -> Poll<F::Output> {
+    let waker = futures::task::waker(flag.clone());
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn async_future_registers_once() -> winrt::Result<()> {
+    let (action, state) = new_action();
+    let mut future = action.clone().into_future();
+
+    let first = Arc::new(Flag(AtomicBool::new(false)));
+    let second = Arc::new(Flag(AtomicBool::new(false)));
+
+    assert!(poll(&mut future, &first).is_pending());
+    assert!(poll(&mut future, &second).is_pending());
+    assert!(state.lock().unwrap().registrations == 1);
+
+    // Only the most recent waker is woken.
+    finish(&action, &state, AsyncStatus::Completed, winrt::ErrorCode(0))?;
+    assert!(!first.0.load(Ordering::SeqCst));
+    assert!(second.0.load(Ordering::SeqCst));
+
+    assert!(matches!(poll(&mut future, &second), Poll::Ready(Ok(()))));
+    drop(future);
+    assert!(!state.lock().unwrap().canceled);
+
+    Ok(())
+}
+
+#[test]
+fn async_future_cancel_on_drop() {
+    let flag = Arc::new(Flag(AtomicBool::new(false)));
+
+    let (action, state) = new_action();
+    let mut future = action.into_future();
+    assert!(poll(&mut future, &flag).is_pending());
+    drop(future);
+    assert!(state.lock().unwrap().canceled);
+
+    let (action, state) = new_action();
+    let mut future = action.into_future().cancel_on_drop(false);
+    assert!(poll(&mut future, &flag).is_pending());
+    drop(future);
+    assert!(!state.lock().unwrap().canceled);
+}
+
+#[test]
+fn async_future_errors() -> winrt::Result<()> {
+    let (action, state) = new_action();
+    let future = action.clone().into_future();
+    finish(&action, &state, AsyncStatus::Canceled, winrt::ErrorCode(0))?;
+
+    let error = futures::executor::block_on(future).unwrap_err();
+    assert!(error.code() == winrt::ErrorCode::ERROR_CANCELLED);
+
+    let (action, state) = new_action();
+    let future = action.clone().into_future();
+    finish(
+        &action,
+        &state,
+        AsyncStatus::Error,
+        winrt::ErrorCode(0x8000_FFFF),
+    )?;
+
+    let error = futures::executor::block_on(future).unwrap_err();
+    assert!(error.code() == winrt::ErrorCode(0x8000_FFFF));
+
+    Ok(())
+}
+
+#[test]
+fn async_operation_from_future() -> winrt::Result<()> {
+    let (operation, task) = winrt::AsyncOperation::<u32>::from_future(async { Ok(42) });
+    let (_, value) =
+        futures::executor::block_on(async { futures::join!(task, operation.into_future()) });
+    assert!(value? == 42);
+
+    let (action, task) =
+        winrt::AsyncAction::from_future(async { Err(winrt::ErrorCode(0x8000_4001).into()) });
+    futures::executor::block_on(task);
+    assert!(action.status()? == AsyncStatus::Error);
+    assert!(action.error_code()? == winrt::ErrorCode(0x8000_4001));
+    assert!(action.get().unwrap_err().code() == winrt::ErrorCode(0x8000_4001));
+
+    Ok(())
+}
+
+#[test]
+fn async_operation_cancel() -> winrt::Result<()> {
+    let (operation, task) =
+        winrt::AsyncOperation::<winrt::HString>::from_future(futures::future::pending());
+
+    operation.cancel()?;
+    assert!(operation.status()? == AsyncStatus::Canceled);
+
+    // The task completes without polling the canceled future.
+    futures::executor::block_on(task);
+
+    let error = futures::executor::block_on(operation.into_future()).unwrap_err();
+    assert!(error.code() == winrt::ErrorCode::ERROR_CANCELLED);
+
+    Ok(())
+}
+
+#[test]
+fn async_operation_with_progress() -> winrt::Result<()> {
+    use futures::StreamExt;
+
+    let (operation, task) =
+        winrt::AsyncOperationWithProgress::<u32, u32>::from_future(|reporter| async move {
+            for value in 1..=3 {
+                reporter.report(&value);
+            }
+
+            Ok(3)
+        });
+
+    let (result, progress) = operation.with_progress()?;
+
+    let (_, result, progress) = futures::executor::block_on(async {
+        futures::join!(task, result, progress.collect::<Vec<u32>>())
+    });
+
+    assert!(result? == 3);
+    assert!(progress == [1, 2, 3]);
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use tests::windows::storage::StorageFile;
+use tests::windows::ui::text::FontWeights;
+use tests::windows::ui::{Colors, NamedColors};
+
+#[test]
+fn renames() -> winrt::Result<()> {
+    // A renamed type is available under both names.
+    assert!(NamedColors::red()? == Colors::red()?);
+
+    Ok(())
+}
+
+#[test]
+fn nested_groups() -> winrt::Result<()> {
+    assert!(FontWeights::bold()?.weight == 700);
+
+    Ok(())
+}
+
+#[test]
+fn members() -> winrt::Result<()> {
+    // Only the selected members of a type are generated.
+    let path = std::env::current_exe().unwrap();
+    let file = StorageFile::get_file_from_path_async(path.to_str().unwrap())?.get()?;
+    assert!(file.name()? == path.file_name().unwrap().to_str().unwrap());
+
+    Ok(())
+}
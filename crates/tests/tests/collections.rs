@@ -1,7 +1,14 @@
+use std::collections::{BTreeMap, HashMap};
 use std::convert::*;
 use std::iter::FromIterator;
-use winrt::foundation::collections::{IIterable, IVectorView, PropertySet};
+use std::sync::{Arc, Mutex};
+use winrt::foundation::collections::{
+    CollectionChange, IIterable, IKeyValuePair, IMap, IMapView, IObservableMap, IObservableVector,
+    IVector, IVectorView, MapChangedEventHandler, PropertySet, VectorChangedEventHandler,
+    VectorViewIterator,
+};
 use winrt::foundation::{IWwwFormUrlDecoderEntry, Uri};
+use winrt::{HString, Interface};
 
 #[test]
 fn uri() -> winrt::Result<()> {
@@ -87,3 +94,259 @@ fn property_set() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn rust_vector() -> winrt::Result<()> {
+    let vector: IVector<i32> = vec![1, 2, 3].into();
+
+    assert!(vector.size()? == 3);
+    assert!(vector.get_at(1)? == 2);
+    assert!(vector.get_at(3).is_err());
+
+    vector.append(4)?;
+    vector.insert_at(0, 0)?;
+    vector.remove_at(1)?;
+    vector.set_at(0, 1)?;
+
+    let mut index = 0;
+    assert!(vector.index_of(3, &mut index)?);
+    assert!(index == 2);
+
+    let view = vector.get_view()?;
+    assert!(view.size()? == 4);
+
+    // The view is read-only so it can't be cast back to the vector.
+    assert!(view.cast::<IVector<i32>>().is_err());
+    assert!(view.cast::<IObservableVector<i32>>().is_err());
+    assert!(view.cast::<IIterable<i32>>()?.into_iter().count() == 4);
+    assert!(view.into_iter().collect::<Vec<i32>>() == [1, 2, 3, 4]);
+
+    vector.replace_all(&[5, 6])?;
+    let iterable: IIterable<i32> = vector.clone().into();
+    assert!(iterable.into_iter().collect::<Vec<i32>>() == [5, 6]);
+
+    let view: IVectorView<HString> = vec!["a".into(), "b".into()].into();
+    assert!(view.get_at(1)? == "b");
+    assert!(view.cast::<IVector<HString>>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn rust_vector_iterator_invalidation() -> winrt::Result<()> {
+    let vector: IVector<i32> = vec![1, 2, 3].into();
+    let iterator = IIterable::<i32>::from(vector.clone()).first()?;
+
+    assert!(iterator.current()? == 1);
+    vector.append(4)?;
+
+    assert!(iterator.current().unwrap_err().code() == winrt::ErrorCode(0x8000_000C));
+    assert!(iterator.move_next().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn rust_observable_vector() -> winrt::Result<()> {
+    let vector: IObservableVector<i32> = vec![1, 2].into();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let handler_changes = changes.clone();
+
+    let token = vector.vector_changed(VectorChangedEventHandler::new(move |sender, args| {
+        assert!(sender.is_some());
+        let args = args.as_ref().unwrap();
+
+        handler_changes
+            .lock()
+            .unwrap()
+            .push((args.collection_change()?, args.index()?));
+
+        Ok(())
+    }))?;
+
+    let inner: IVector<i32> = vector.clone().into();
+    inner.append(3)?;
+    inner.set_at(0, 0)?;
+    inner.remove_at_end()?;
+    vector.remove_vector_changed(token)?;
+    inner.append(4)?;
+
+    assert!(
+        *changes.lock().unwrap()
+            == [
+                (CollectionChange::ItemInserted, 2),
+                (CollectionChange::ItemChanged, 0),
+                (CollectionChange::ItemRemoved, 2)
+            ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn rust_map() -> winrt::Result<()> {
+    let mut values = HashMap::new();
+    values.insert(HString::from("a"), 1);
+    values.insert(HString::from("b"), 2);
+
+    let map: IMap<HString, i32> = values.into();
+
+    assert!(map.size()? == 2);
+    assert!(map.lookup("b")? == 2);
+    assert!(map.has_key("a")?);
+    assert!(!map.has_key("c")?);
+    assert!(map.lookup("c").is_err());
+
+    assert!(!map.insert("c", 3)?);
+    assert!(map.insert("c", 4)?);
+    map.remove("a")?;
+    assert!(map.remove("a").is_err());
+
+    let view = map.get_view()?;
+    assert!(view.size()? == 2);
+    assert!(view.cast::<IMap<HString, i32>>().is_err());
+    assert!(view.cast::<IObservableMap<HString, i32>>().is_err());
+
+    let iterable: IIterable<IKeyValuePair<HString, i32>> = map.into();
+    let mut pairs: Vec<(String, i32)> = iterable
+        .into_iter()
+        .map(|pair| (pair.key().unwrap().to_string(), pair.value().unwrap()))
+        .collect();
+
+    pairs.sort();
+    assert!(pairs == [("b".to_string(), 2), ("c".to_string(), 4)]);
+
+    let mut values = BTreeMap::new();
+    values.insert(2, HString::from("two"));
+    values.insert(1, HString::from("one"));
+
+    let view: IMapView<i32, HString> = values.into();
+    let iterable: IIterable<IKeyValuePair<i32, HString>> = view.into();
+    let keys: Vec<i32> = iterable
+        .into_iter()
+        .map(|pair| pair.key().unwrap())
+        .collect();
+    assert!(keys == [1, 2]);
+
+    Ok(())
+}
+
+#[test]
+fn rust_observable_map() -> winrt::Result<()> {
+    let map: IObservableMap<HString, i32> = BTreeMap::new().into();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let handler_changes = changes.clone();
+
+    map.map_changed(MapChangedEventHandler::new(move |_sender, args| {
+        let args = args.as_ref().unwrap();
+
+        handler_changes
+            .lock()
+            .unwrap()
+            .push((args.collection_change()?, args.key()?.to_string()));
+
+        Ok(())
+    }))?;
+
+    let inner: IMap<HString, i32> = map.into();
+    let iterator = IIterable::<IKeyValuePair<HString, i32>>::from(inner.clone()).first()?;
+
+    inner.insert("a", 1)?;
+    inner.insert("a", 2)?;
+    inner.remove("a")?;
+    inner.clear()?;
+
+    assert!(iterator.has_current().is_err());
+
+    assert!(
+        *changes.lock().unwrap()
+            == [
+                (CollectionChange::ItemInserted, "a".to_string()),
+                (CollectionChange::ItemChanged, "a".to_string()),
+                (CollectionChange::ItemRemoved, "a".to_string()),
+                (CollectionChange::Reset, String::new())
+            ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn chunked_iteration() -> winrt::Result<()> {
+    let values: Vec<i32> = (0..100).collect();
+    let view: IVectorView<i32> = values.clone().into();
+
+    let iterator = VectorViewIterator::with_chunk_size(view.clone(), 7);
+    assert!(iterator.len() == 100);
+    assert!(iterator.collect::<Vec<i32>>() == values);
+
+    let reversed: Vec<i32> = view.clone().into_iter().rev().collect();
+    assert!(reversed.iter().rev().eq(values.iter()));
+
+    let mut iterator = VectorViewIterator::with_chunk_size(view.clone(), 8);
+    assert!(iterator.next() == Some(0));
+    assert!(iterator.next_back() == Some(99));
+    assert!(iterator.len() == 98);
+
+    assert!(view.to_vec()? == values);
+
+    let iterable: IIterable<i32> = values.clone().into();
+    assert!(iterable.first()?.chunked(9).collect::<Vec<i32>>() == values);
+    assert!(iterable.into_iter().collect::<Vec<i32>>() == values);
+
+    Ok(())
+}
+
+#[test]
+fn vector_helpers() -> winrt::Result<()> {
+    let mut vector: IVector<i32> = (1..=6).collect();
+
+    assert!(vector.len()? == 6);
+    assert!(!vector.is_empty()?);
+    assert!(vector.get(2)? == Some(3));
+    assert!(vector.get(6)?.is_none());
+
+    vector.push(7)?;
+    vector.extend(vec![8]);
+    vector.try_extend(vec![9])?;
+    vector.retain(|value| value % 3 != 0)?;
+    assert!(vector.to_vec()? == [1, 2, 4, 5, 7, 8]);
+
+    vector.retain(|_| false)?;
+    assert!(vector.is_empty()?);
+
+    let view: IVectorView<HString> = ["a", "b"]
+        .iter()
+        .map(|value| HString::from(*value))
+        .collect();
+    assert!(view.get(1)?.unwrap() == "b");
+
+    Ok(())
+}
+
+#[test]
+fn map_helpers() -> winrt::Result<()> {
+    let mut map: IMap<HString, i32> = BTreeMap::new().into();
+    map.extend(vec![("a".into(), 1), ("b".into(), 2)]);
+    map.try_extend(vec![("c".into(), 3)])?;
+
+    assert!(map.len()? == 3);
+    assert!(map.get("b")? == Some(2));
+    assert!(map.get("d")?.is_none());
+    assert!(map.contains_key("c")?);
+    assert!(!map.contains_key("d")?);
+
+    map.retain(|key, _| *key != "b")?;
+
+    let pairs: Vec<(String, i32)> = (&map)
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+    assert!(pairs == [("a".to_string(), 1), ("c".to_string(), 3)]);
+
+    let view = map.get_view()?;
+    assert!(view.len()? == 2);
+    assert!((&view).into_iter().map(|(_, value)| value).sum::<i32>() == 4);
+
+    Ok(())
+}
@@ -89,3 +89,24 @@ fn event() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn event_revoker() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let revoker = set.on_map_changed(move |_, args| {
+        tx.send(args.as_ref().unwrap().key()? == "A").unwrap();
+        Ok(())
+    })?;
+
+    set.insert("A", winrt::Object::try_from(1_u32)?)?;
+    assert!(rx.recv().unwrap());
+
+    // Dropping the revoker removes the handler so no further changes are observed.
+    drop(revoker);
+    set.insert("B", winrt::Object::try_from(2_u32)?)?;
+    assert!(rx.try_recv().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use winrt::foundation::{IMemoryBufferReference, MemoryBuffer};
+use winrt::{EventStream, Overflow};
+
+#[test]
+fn event_stream() -> winrt::Result<()> {
+    let buffer = MemoryBuffer::create(16)?;
+    let reference = buffer.create_reference()?;
+
+    let mut closed = EventStream::typed(
+        &reference,
+        4,
+        Overflow::DropOldest,
+        |reference, handler| reference.closed(handler),
+        |reference, token| reference.remove_closed(token),
+    )?;
+
+    buffer.close()?;
+
+    let (sender, _) = futures::executor::block_on(closed.next()).unwrap();
+    let sender: IMemoryBufferReference = sender.unwrap();
+    assert!(sender == reference);
+    assert!(closed.dropped() == 0);
+
+    Ok(())
+}
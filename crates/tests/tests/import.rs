@@ -0,0 +1,15 @@
+winrt::import!(windows::data::xml::dom::XmlDocument);
+
+use windows::data::xml::dom::XmlDocument;
+
+// The bindings are generated in place without a build script.
+#[test]
+fn import() -> winrt::Result<()> {
+    let doc = XmlDocument::new()?;
+
+    doc.load_xml("<html>hello world</html>")?;
+    let root = doc.document_element()?;
+    assert!(root.node_name()? == "html");
+
+    Ok(())
+}
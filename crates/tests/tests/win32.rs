@@ -1,6 +1,6 @@
 use tests::windows::win32::{
-    ACCESS_MODE, CHOOSECOLORW, DXGI_ADAPTER_FLAG, DXGI_FORMAT, DXGI_MODE_DESC, DXGI_MODE_SCALING,
-    DXGI_MODE_SCANLINE_ORDER, DXGI_RATIONAL, RECT,
+    WNDENUMPROC_closure, WNDPROC_closure, ACCESS_MODE, CHOOSECOLORW, DXGI_ADAPTER_FLAG,
+    DXGI_FORMAT, DXGI_MODE_DESC, DXGI_MODE_SCALING, DXGI_MODE_SCANLINE_ORDER, DXGI_RATIONAL, RECT,
 };
 use winrt::Abi;
 
@@ -65,6 +65,32 @@ fn dxgi_mode_desc() {
     };
 }
 
+#[test]
+fn callback_with_context() {
+    let mut windows = Vec::new();
+
+    let mut enum_windows = |window| {
+        windows.push(window);
+        1
+    };
+
+    let (callback, context) = unsafe { WNDENUMPROC_closure::with_context(&mut enum_windows) };
+
+    assert!(callback(1, context) == 1);
+    assert!(callback(2, context) == 1);
+    assert!(windows == [1, 2]);
+}
+
+#[test]
+fn callback_with_stash() {
+    let callback =
+        WNDPROC_closure::set(|_window, message, _w_param, _l_param| message as isize * 2);
+    assert!(callback(0, 21, 0, 0) == 42);
+
+    WNDPROC_closure::clear();
+    assert!(callback(0, 21, 0, 0) == 0);
+}
+
 #[cfg(target_pointer_width = "64")]
 #[test]
 fn size64() {
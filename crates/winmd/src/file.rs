@@ -11,6 +11,8 @@ pub struct TableData {
 /// A Windows Metadata File
 #[derive(Default)]
 pub struct File {
+    /// The path the file was read from
+    pub path: std::path::PathBuf,
     /// The files raw bytes
     pub(crate) bytes: Vec<u8>,
     /// The index of the strings data
@@ -79,6 +81,11 @@ impl TableData {
 }
 
 impl File {
+    /// The file's raw bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
     /// Parse a Windows metadata file at the given path
     ///
     /// # Panics
@@ -88,6 +95,7 @@ impl File {
         let bytes = std::fs::read(filename.as_ref())
             .unwrap_or_else(|e| panic!("Could not read file {:?}: {:?}", filename.as_ref(), e));
         let mut file = Self {
+            path: filename.as_ref().to_path_buf(),
             bytes,
             ..Default::default()
         };
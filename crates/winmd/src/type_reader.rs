@@ -18,18 +18,75 @@ pub struct TypeReader {
 }
 
 impl TypeReader {
+    /// The metadata used by the current build
+    ///
+    /// The metadata is loaded from the paths in the `WINRT_METADATA_PATH` environment variable if
+    /// it's set, otherwise from the `metadata` paths in the `[package.metadata.winrt]` table of the
+    /// Cargo.toml of the package being built. Without either the `.windows/winmd` directory in the
+    /// root of the workspace is used, falling back to the metadata installed with Windows.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the configured paths don't exist or can't be read.
     pub fn from_build() -> &'static Self {
+        Self::try_from_build().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// The metadata used by the current build, as with [`TypeReader::from_build`], or an error if
+    /// any of the configured paths don't exist
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the files can't be read.
+    pub fn try_from_build() -> Result<&'static Self, MetadataNotFound> {
         use std::{mem::MaybeUninit, sync::Once};
         static ONCE: Once = Once::new();
-        static mut VALUE: MaybeUninit<TypeReader> = MaybeUninit::uninit();
+        static mut VALUE: MaybeUninit<Result<TypeReader, MetadataNotFound>> = MaybeUninit::uninit();
 
         ONCE.call_once(|| {
             // This is safe because `Once` provides thread-safe one-time initialization
-            unsafe { VALUE = MaybeUninit::new(Self::from_iter(winmd_paths())) }
+            unsafe { VALUE = MaybeUninit::new(winmd_paths().map(Self::from_iter)) }
         });
 
         // This is safe because `call_once` has already been called.
-        unsafe { &*VALUE.as_ptr() }
+        unsafe { (*VALUE.as_ptr()).as_ref().map_err(Clone::clone) }
+    }
+
+    /// The metadata in the given files and directories of winmd files, or an error if any of the
+    /// paths don't exist
+    ///
+    /// Readers are cached by the paths they're loaded from so that loading the same metadata again
+    /// returns the same reader.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the files can't be read.
+    pub fn from_paths<P: AsRef<std::path::Path>>(
+        paths: &[P],
+    ) -> Result<&'static Self, MetadataNotFound> {
+        static READERS: std::sync::Mutex<Vec<(Vec<PathBuf>, &'static TypeReader)>> =
+            std::sync::Mutex::new(Vec::new());
+
+        let mut key = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let path = path.as_ref();
+
+            key.push(std::fs::canonicalize(path).map_err(|_| MetadataNotFound {
+                path: path.to_path_buf(),
+            })?);
+        }
+
+        let mut readers = READERS.lock().unwrap();
+
+        if let Some((_, reader)) = readers.iter().find(|(paths, _)| *paths == key) {
+            return Ok(reader);
+        }
+
+        // The reader is leaked since types refer to it for the remainder of the build.
+        let reader = Box::leak(Box::new(Self::from_iter(winmd_files(&key)?)));
+        readers.push((key, reader));
+        Ok(reader)
     }
 
     /// Insert WinRT metadata at the given paths
@@ -310,7 +367,18 @@ impl TypeReader {
     }
 }
 
-fn winmd_paths() -> Vec<std::path::PathBuf> {
+fn winmd_paths() -> Result<Vec<std::path::PathBuf>, MetadataNotFound> {
+    if let Some(paths) = std::env::var_os("WINRT_METADATA_PATH") {
+        let paths: Vec<_> = std::env::split_paths(&paths).collect();
+        return winmd_files(&paths);
+    }
+
+    let paths = package_metadata_paths();
+
+    if !paths.is_empty() {
+        return winmd_files(&paths);
+    }
+
     let mut windows_path = workspace_windows_dir();
     windows_path.push("winmd");
 
@@ -330,10 +398,36 @@ fn winmd_paths() -> Vec<std::path::PathBuf> {
         }
     }
 
-    paths
+    Ok(paths)
+}
+
+/// Expands the paths of winmd files and directories into the paths of the winmd files, or returns
+/// the first path that doesn't exist
+fn winmd_files<P: AsRef<std::path::Path>>(
+    paths: &[P],
+) -> Result<Vec<std::path::PathBuf>, MetadataNotFound> {
+    let mut files = vec![];
+
+    for path in paths {
+        let path = path.as_ref();
+
+        if path.is_dir() {
+            push_winmd_paths(path.to_path_buf(), &mut files);
+        } else if path.is_file() {
+            files.push(path.to_path_buf());
+        } else {
+            return Err(MetadataNotFound {
+                path: path.to_path_buf(),
+            });
+        }
+    }
+
+    Ok(files)
 }
 
 fn push_winmd_paths(dir: std::path::PathBuf, paths: &mut Vec<std::path::PathBuf>) {
+    let mut winmd = vec![];
+
     if let Ok(files) = std::fs::read_dir(dir) {
         for file in files.filter_map(|file| file.ok()) {
             if let Ok(file_type) = file.file_type() {
@@ -341,14 +435,33 @@ fn push_winmd_paths(dir: std::path::PathBuf, paths: &mut Vec<std::path::PathBuf>
                     let path = file.path();
                     if let Some("winmd") = path.extension().and_then(|extension| extension.to_str())
                     {
-                        paths.push(file.path());
+                        winmd.push(file.path());
                     }
                 }
             }
         }
     }
+
+    // Directories are read in an unspecified order but the first definition of a type wins, so
+    // the files are sorted to keep builds deterministic.
+    winmd.sort();
+    paths.append(&mut winmd);
 }
 
+/// A metadata path that doesn't exist
+#[derive(Clone, Debug)]
+pub struct MetadataNotFound {
+    pub path: PathBuf,
+}
+
+impl std::fmt::Display for MetadataNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not find metadata at {:?}", self.path)
+    }
+}
+
+impl std::error::Error for MetadataNotFound {}
+
 #[cfg(target_pointer_width = "64")]
 const SYSTEM32: &str = "System32";
 
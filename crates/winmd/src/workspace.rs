@@ -1,14 +1,53 @@
 /// Returns the build's `.windows` directory in the root of the workspace as a `PathBuf`.
 pub fn workspace_windows_dir() -> std::path::PathBuf {
-    let mut path = workspace_dir();
+    let mut path = workspace().root.clone();
     path.push(".windows");
     path
 }
 
-fn workspace_dir() -> std::path::PathBuf {
+/// Returns the metadata paths configured for the package being built as a `Vec<PathBuf>`.
+///
+/// The paths are read from the `metadata` array of the `[package.metadata.winrt]` table in the
+/// package's Cargo.toml and are relative to the package's directory:
+///
+/// ```toml
+/// [package.metadata.winrt]
+/// metadata = ["metadata", "../component/Component.winmd"]
+/// ```
+pub fn package_metadata_paths() -> Vec<std::path::PathBuf> {
+    let manifest_dir = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => return Vec::new(),
+    };
+
+    let package = workspace().packages.iter().find(|package| {
+        std::path::Path::new(&package.manifest_path).parent() == Some(manifest_dir.as_path())
+    });
+
+    // Other tools' metadata is left unparsed so that it can't cause the build to fail.
+    package
+        .and_then(|package| package.metadata.as_ref())
+        .and_then(|metadata| metadata.pointer("/winrt/metadata"))
+        .and_then(|paths| paths.as_array())
+        .map(|paths| {
+            paths
+                .iter()
+                .filter_map(|path| path.as_str())
+                .map(|path| manifest_dir.join(path))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+struct Workspace {
+    root: std::path::PathBuf,
+    packages: Vec<Package>,
+}
+
+fn workspace() -> &'static Workspace {
     use std::{mem::MaybeUninit, sync::Once};
     static ONCE: Once = Once::new();
-    static mut VALUE: MaybeUninit<std::path::PathBuf> = MaybeUninit::uninit();
+    static mut VALUE: MaybeUninit<Workspace> = MaybeUninit::uninit();
 
     ONCE.call_once(|| {
         let output = std::process::Command::new(env!("CARGO"))
@@ -20,15 +59,27 @@ fn workspace_dir() -> std::path::PathBuf {
         let manifest: Manifest =
             serde_json::from_slice(&output.stdout).expect("Failed to parse `cargo metadata`");
 
+        let workspace = Workspace {
+            root: manifest.workspace_root.into(),
+            packages: manifest.packages,
+        };
+
         // This is safe because `Once` provides thread-safe one-time initialization
-        unsafe { VALUE = MaybeUninit::new(manifest.workspace_root.into()) }
+        unsafe { VALUE = MaybeUninit::new(workspace) }
     });
 
     // This is safe because `call_once` has already been called.
-    unsafe { (*VALUE.as_ptr()).clone() }
+    unsafe { &*VALUE.as_ptr() }
 }
 
 #[derive(serde::Deserialize)]
 struct Manifest {
     workspace_root: String,
+    packages: Vec<Package>,
+}
+
+#[derive(serde::Deserialize)]
+struct Package {
+    manifest_path: String,
+    metadata: Option<serde_json::Value>,
 }
@@ -0,0 +1,21 @@
+#[test]
+fn from_paths() {
+    let file = "../../.windows/winmd/Windows.Foundation.FoundationContract.winmd";
+    let reader = winmd::TypeReader::from_paths(&[file]).unwrap();
+
+    let def = reader.resolve_type_def(("Windows.Foundation", "IStringable"));
+    assert!(def.name() == ("Windows.Foundation", "IStringable"));
+
+    // The same metadata is only loaded once.
+    let same = winmd::TypeReader::from_paths(&[
+        "../../.windows/winmd/../winmd/Windows.Foundation.FoundationContract.winmd",
+    ])
+    .unwrap();
+    assert!(std::ptr::eq(reader, same));
+
+    let error = winmd::TypeReader::from_paths(&[file, "missing.winmd"])
+        .err()
+        .unwrap();
+    assert!(error.path == std::path::Path::new("missing.winmd"));
+    assert!(error.to_string() == "Could not find metadata at \"missing.winmd\"");
+}
@@ -5,6 +5,8 @@ mod language_exception_error_info2;
 mod object;
 mod restricted_error_info;
 mod unknown;
+mod weak_reference;
+mod weak_reference_source;
 
 pub use activation_factory::*;
 pub use agile_object::*;
@@ -13,3 +15,5 @@ pub use language_exception_error_info2::*;
 pub use object::*;
 pub use restricted_error_info::*;
 pub use unknown::*;
+pub use weak_reference::*;
+pub use weak_reference_source::*;
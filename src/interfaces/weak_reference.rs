@@ -0,0 +1,45 @@
+use crate::*;
+
+/// A weak reference to a COM object. `IWeakReference` represents the
+/// [IWeakReference](https://docs.microsoft.com/en-us/windows/win32/api/weakreference/nn-weakreference-iweakreference)
+/// interface.
+#[repr(transparent)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct IWeakReference(IUnknown);
+
+#[repr(C)]
+pub struct IWeakReference_vtable(
+    pub unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode,
+    pub unsafe extern "system" fn(this: RawPtr) -> u32,
+    pub unsafe extern "system" fn(this: RawPtr) -> u32,
+    pub unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode, // Resolve
+);
+
+impl IWeakReference {
+    /// Attempts to get a strong reference to the object. Returns `None` if the object has been
+    /// destroyed or does not implement the requested interface.
+    pub fn resolve<T: Interface>(&self) -> Option<T> {
+        unsafe {
+            let mut result = None;
+            (self.vtable().3)(self.abi(), &T::IID, &mut result as *mut _ as _);
+            result
+        }
+    }
+}
+
+unsafe impl Interface for IWeakReference {
+    type Vtable = IWeakReference_vtable;
+
+    const IID: Guid = Guid::from_values(
+        0x0000_0037,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+impl std::fmt::Debug for IWeakReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
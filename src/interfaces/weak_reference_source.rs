@@ -0,0 +1,43 @@
+use crate::*;
+
+/// Implemented by objects that support weak references. `IWeakReferenceSource` represents the
+/// [IWeakReferenceSource](https://docs.microsoft.com/en-us/windows/win32/api/weakreference/nn-weakreference-iweakreferencesource)
+/// interface.
+#[repr(transparent)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct IWeakReferenceSource(IUnknown);
+
+#[repr(C)]
+pub struct IWeakReferenceSource_vtable(
+    pub unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode,
+    pub unsafe extern "system" fn(this: RawPtr) -> u32,
+    pub unsafe extern "system" fn(this: RawPtr) -> u32,
+    pub unsafe extern "system" fn(this: RawPtr, reference: *mut RawPtr) -> ErrorCode, // GetWeakReference
+);
+
+impl IWeakReferenceSource {
+    /// Creates a weak reference to the object.
+    pub fn weak_reference(&self) -> Result<IWeakReference> {
+        unsafe {
+            let mut result = None;
+            (self.vtable().3)(self.abi(), &mut result as *mut _ as _).and_some(result)
+        }
+    }
+}
+
+unsafe impl Interface for IWeakReferenceSource {
+    type Vtable = IWeakReferenceSource_vtable;
+
+    const IID: Guid = Guid::from_values(
+        0x0000_0038,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+impl std::fmt::Debug for IWeakReferenceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
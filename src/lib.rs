@@ -29,10 +29,15 @@ use strings::*;
 
 pub use interfaces::{IActivationFactory, IAgileObject, IUnknown, Object};
 pub use result::{Error, ErrorCode, Result};
-pub use runtime::{Array, FactoryCache, Guid, Param, RefCount, Waiter};
+pub use runtime::{
+    get_chunk, Array, Async, AsyncAction, AsyncActionWithProgress, AsyncFuture, AsyncKind,
+    AsyncOperation, AsyncOperationWithProgress, AsyncProgress, AsyncProgressReporter, AsyncTask,
+    EventRevoker, EventStream, EventStreamNext, FactoryCache, Guid, Overflow, Param, RefCount,
+    Waiter, DEFAULT_CHUNK_SIZE,
+};
 pub use strings::HString;
 pub use traits::{Abi, Interface, RuntimeName, RuntimeType};
-pub use winrt_macros::{build, implement};
+pub use winrt_macros::{build, implement, import};
 
 extern crate self as winrt;
 
@@ -48,3 +53,6 @@ pub use bindings::windows::foundation;
 
 #[doc(hidden)]
 pub use const_sha1::ConstBuffer;
+
+#[doc(hidden)]
+pub use runtime::abort_on_unwind;
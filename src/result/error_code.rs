@@ -102,6 +102,15 @@ impl ErrorCode {
 
     /// A null pointer was sent or received.
     pub const E_POINTER: ErrorCode = ErrorCode(0x8000_4003);
+
+    /// A collection index or key is out of bounds.
+    pub const E_BOUNDS: ErrorCode = ErrorCode(0x8000_000B);
+
+    /// An unspecified failure occurred.
+    pub const E_FAIL: ErrorCode = ErrorCode(0x8000_4005);
+
+    /// The operation was canceled.
+    pub const ERROR_CANCELLED: ErrorCode = ErrorCode(0x8007_04C7);
 }
 
 unsafe impl Abi for ErrorCode {
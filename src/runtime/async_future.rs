@@ -0,0 +1,166 @@
+use crate::foundation::{AsyncStatus, IAsyncInfo};
+use crate::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Implemented by the generated `IAsyncAction`, `IAsyncActionWithProgress`, `IAsyncOperation` and
+/// `IAsyncOperationWithProgress` bindings so that they can be awaited and should not be
+/// implemented directly.
+pub trait Async: Interface + Clone {
+    /// The type of the operation's results.
+    type Output;
+
+    /// Returns the operation's `IAsyncInfo` interface.
+    fn info(&self) -> IAsyncInfo;
+
+    /// Registers `callback` as the operation's completion handler. WinRT only permits a single
+    /// completion handler per operation.
+    fn set_completed_callback<F: FnMut() + 'static>(&self, callback: F) -> Result<()>;
+
+    /// Retrieves the results of an operation that completed successfully.
+    fn results(&self) -> Result<Self::Output>;
+}
+
+/// A future that resolves to the results of a WinRT async operation, returned by `into_future`
+/// or implicitly by `.await`. The operations themselves implement `IntoFuture` rather than
+/// `Future` since they have nowhere to keep the state shared with the completion handler, so
+/// `into_future` must be called where a `Future` is expected, as with `block_on(op.into_future())`.
+///
+/// The completion handler is registered the first time the future is polled and the most recent
+/// waker is kept in state shared with the handler. If the future is dropped before the operation
+/// completes the operation is canceled, unless disabled with [`AsyncFuture::cancel_on_drop`].
+pub struct AsyncFuture<T: Async> {
+    operation: T,
+    info: IAsyncInfo,
+    state: Option<Arc<Mutex<AsyncState>>>,
+    cancel_on_drop: bool,
+    finished: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct AsyncState {
+    pub completed: bool,
+    pub waker: Option<Waker>,
+    pub progress_waker: Option<Waker>,
+}
+
+impl<T: Async> AsyncFuture<T> {
+    pub fn new(operation: T) -> Self {
+        let info = operation.info();
+
+        Self {
+            operation,
+            info,
+            state: None,
+            cancel_on_drop: true,
+            finished: false,
+        }
+    }
+
+    /// Sets whether the operation is canceled if the future is dropped before it completes.
+    /// This is `true` by default.
+    pub fn cancel_on_drop(mut self, cancel: bool) -> Self {
+        self.cancel_on_drop = cancel;
+        self
+    }
+
+    /// Returns the operation being awaited.
+    pub fn operation(&self) -> &T {
+        &self.operation
+    }
+
+    /// Registers the completion handler if it hasn't been registered already, returning the
+    /// state shared with the handler.
+    pub(crate) fn register(&mut self) -> Result<Arc<Mutex<AsyncState>>> {
+        if let Some(state) = &self.state {
+            return Ok(state.clone());
+        }
+
+        let state = Arc::new(Mutex::new(AsyncState::default()));
+        let handler_state = state.clone();
+
+        // The handler may be called before `set_completed_callback` returns, including on this
+        // thread if the operation has already completed, so the lock isn't held here.
+        self.operation.set_completed_callback(move || {
+            let (waker, progress_waker) = {
+                let mut state = handler_state.lock().unwrap();
+                state.completed = true;
+                (state.waker.take(), state.progress_waker.take())
+            };
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+
+            if let Some(waker) = progress_waker {
+                waker.wake();
+            }
+        })?;
+
+        self.state = Some(state.clone());
+        Ok(state)
+    }
+
+    fn results(&mut self) -> Result<T::Output> {
+        self.finished = true;
+
+        let status = self.info.status()?;
+
+        if status == AsyncStatus::Canceled {
+            Err(Error::from(ErrorCode::ERROR_CANCELLED))
+        } else if status == AsyncStatus::Error {
+            let code = self.info.error_code()?;
+
+            Err(Error::from(if code.is_err() {
+                code
+            } else {
+                ErrorCode::E_FAIL
+            }))
+        } else {
+            self.operation.results()
+        }
+    }
+}
+
+impl<T: Async> Unpin for AsyncFuture<T> {}
+
+impl<T: Async> Future for AsyncFuture<T> {
+    type Output = Result<T::Output>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if this.state.is_none() && this.info.status()? != AsyncStatus::Started {
+            return Poll::Ready(this.results());
+        }
+
+        let state = this.register()?;
+        let mut state = state.lock().unwrap();
+
+        if state.completed {
+            drop(state);
+            Poll::Ready(this.results())
+        } else {
+            state.waker = Some(context.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: Async> Drop for AsyncFuture<T> {
+    fn drop(&mut self) {
+        if self.cancel_on_drop && !self.finished {
+            if self.info.status() == Ok(AsyncStatus::Started) {
+                let _ = self.info.cancel();
+            }
+        }
+    }
+}
+
+impl<T: Async + std::fmt::Debug> std::fmt::Debug for AsyncFuture<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AsyncFuture({:?})", self.operation)
+    }
+}
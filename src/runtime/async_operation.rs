@@ -0,0 +1,690 @@
+use crate::foundation::{
+    AsyncStatus, IAsyncAction, IAsyncActionWithProgress, IAsyncActionWithProgress_abi,
+    IAsyncAction_abi, IAsyncInfo, IAsyncInfo_abi, IAsyncOperation, IAsyncOperationWithProgress,
+    IAsyncOperationWithProgress_abi, IAsyncOperation_abi,
+};
+use crate::*;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+// Error codes defined by the WinRT async pattern.
+const E_ILLEGAL_STATE_CHANGE: ErrorCode = ErrorCode(0x8000_000D);
+const E_ILLEGAL_METHOD_CALL: ErrorCode = ErrorCode(0x8000_000E);
+const E_ILLEGAL_DELEGATE_ASSIGNMENT: ErrorCode = ErrorCode(0x8000_0018);
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Creates an `IAsyncAction` implemented by a Rust future.
+///
+/// ```ignore
+/// let (action, task) = winrt::AsyncAction::from_future(async { Ok(()) });
+/// executor.spawn(task);
+/// ```
+pub struct AsyncAction;
+
+/// Creates an `IAsyncActionWithProgress<P>` implemented by a Rust future.
+pub struct AsyncActionWithProgress<P>(PhantomData<P>);
+
+/// Creates an `IAsyncOperation<T>` implemented by a Rust future.
+pub struct AsyncOperation<T>(PhantomData<T>);
+
+/// Creates an `IAsyncOperationWithProgress<T, P>` implemented by a Rust future.
+pub struct AsyncOperationWithProgress<T, P>(PhantomData<(T, P)>);
+
+impl AsyncAction {
+    /// Returns an `IAsyncAction` that completes when `future` does, along with the [`AsyncTask`]
+    /// that drives `future` and must be spawned on an executor.
+    pub fn from_future<F: Future<Output = Result<()>>>(
+        future: F,
+    ) -> (IAsyncAction, AsyncTask<Self, F>) {
+        AsyncBox::<Self>::create(future)
+    }
+}
+
+impl<T: RuntimeType + 'static> AsyncOperation<T>
+where
+    T::DefaultType: Clone,
+{
+    /// Returns an `IAsyncOperation<T>` that completes with the output of `future`, along with
+    /// the [`AsyncTask`] that drives `future` and must be spawned on an executor.
+    pub fn from_future<F: Future<Output = Result<T::DefaultType>>>(
+        future: F,
+    ) -> (IAsyncOperation<T>, AsyncTask<Self, F>) {
+        AsyncBox::<Self>::create(future)
+    }
+}
+
+impl<P: RuntimeType + 'static> AsyncActionWithProgress<P> {
+    /// Returns an `IAsyncActionWithProgress<P>` that completes when the future returned by
+    /// `start` does, along with the [`AsyncTask`] that drives it. The future reports progress
+    /// through the [`AsyncProgressReporter`] passed to `start`.
+    pub fn from_future<F, S>(start: S) -> (IAsyncActionWithProgress<P>, AsyncTask<Self, F>)
+    where
+        F: Future<Output = Result<()>>,
+        S: FnOnce(AsyncProgressReporter<P>) -> F,
+    {
+        AsyncBox::<Self>::create_with_progress(start)
+    }
+}
+
+impl<T: RuntimeType + 'static, P: RuntimeType + 'static> AsyncOperationWithProgress<T, P>
+where
+    T::DefaultType: Clone,
+{
+    /// Returns an `IAsyncOperationWithProgress<T, P>` that completes with the output of the
+    /// future returned by `start`, along with the [`AsyncTask`] that drives it. The future
+    /// reports progress through the [`AsyncProgressReporter`] passed to `start`.
+    pub fn from_future<F, S>(start: S) -> (IAsyncOperationWithProgress<T, P>, AsyncTask<Self, F>)
+    where
+        F: Future<Output = Result<T::DefaultType>>,
+        S: FnOnce(AsyncProgressReporter<P>) -> F,
+    {
+        AsyncBox::<Self>::create_with_progress(start)
+    }
+}
+
+/// Describes one of the WinRT async interfaces implemented by [`AsyncAction`],
+/// [`AsyncActionWithProgress`], [`AsyncOperation`] and [`AsyncOperationWithProgress`] and should
+/// not be implemented directly.
+pub trait AsyncKind: Sized + 'static {
+    /// The async interface implemented.
+    type Interface: Interface;
+
+    /// The type of the operation's results.
+    type Output: Clone;
+
+    #[doc(hidden)]
+    const NAME: &'static str;
+
+    #[doc(hidden)]
+    const VTABLE: &'static <Self::Interface as Interface>::Vtable;
+}
+
+impl AsyncKind for AsyncAction {
+    type Interface = IAsyncAction;
+    type Output = ();
+
+    const NAME: &'static str = "Windows.Foundation.IAsyncAction";
+
+    const VTABLE: &'static IAsyncAction_abi = &IAsyncAction_abi(
+        AsyncBox::<Self>::QueryInterface,
+        AsyncBox::<Self>::AddRef,
+        AsyncBox::<Self>::Release,
+        AsyncBox::<Self>::GetIids,
+        AsyncBox::<Self>::GetRuntimeClassName,
+        AsyncBox::<Self>::GetTrustLevel,
+        AsyncBox::<Self>::SetCompleted,
+        AsyncBox::<Self>::GetCompleted,
+        AsyncBox::<Self>::GetResults,
+    );
+}
+
+impl<P: RuntimeType + 'static> AsyncKind for AsyncActionWithProgress<P> {
+    type Interface = IAsyncActionWithProgress<P>;
+    type Output = ();
+
+    const NAME: &'static str = "Windows.Foundation.IAsyncActionWithProgress`1";
+
+    const VTABLE: &'static IAsyncActionWithProgress_abi<P> = &IAsyncActionWithProgress_abi(
+        AsyncBox::<Self>::QueryInterface,
+        AsyncBox::<Self>::AddRef,
+        AsyncBox::<Self>::Release,
+        AsyncBox::<Self>::GetIids,
+        AsyncBox::<Self>::GetRuntimeClassName,
+        AsyncBox::<Self>::GetTrustLevel,
+        AsyncBox::<Self>::SetProgress,
+        AsyncBox::<Self>::GetProgress,
+        AsyncBox::<Self>::SetCompleted,
+        AsyncBox::<Self>::GetCompleted,
+        AsyncBox::<Self>::GetResults,
+        PhantomData,
+    );
+}
+
+impl<T: RuntimeType + 'static> AsyncKind for AsyncOperation<T>
+where
+    T::DefaultType: Clone,
+{
+    type Interface = IAsyncOperation<T>;
+    type Output = T::DefaultType;
+
+    const NAME: &'static str = "Windows.Foundation.IAsyncOperation`1";
+
+    const VTABLE: &'static IAsyncOperation_abi<T> = &IAsyncOperation_abi(
+        AsyncBox::<Self>::QueryInterface,
+        AsyncBox::<Self>::AddRef,
+        AsyncBox::<Self>::Release,
+        AsyncBox::<Self>::GetIids,
+        AsyncBox::<Self>::GetRuntimeClassName,
+        AsyncBox::<Self>::GetTrustLevel,
+        AsyncBox::<Self>::SetCompleted,
+        AsyncBox::<Self>::GetCompleted,
+        AsyncBox::<Self>::GetResultsValue::<T::Abi>,
+        PhantomData,
+    );
+}
+
+impl<T: RuntimeType + 'static, P: RuntimeType + 'static> AsyncKind
+    for AsyncOperationWithProgress<T, P>
+where
+    T::DefaultType: Clone,
+{
+    type Interface = IAsyncOperationWithProgress<T, P>;
+    type Output = T::DefaultType;
+
+    const NAME: &'static str = "Windows.Foundation.IAsyncOperationWithProgress`2";
+
+    const VTABLE: &'static IAsyncOperationWithProgress_abi<T, P> = &IAsyncOperationWithProgress_abi(
+        AsyncBox::<Self>::QueryInterface,
+        AsyncBox::<Self>::AddRef,
+        AsyncBox::<Self>::Release,
+        AsyncBox::<Self>::GetIids,
+        AsyncBox::<Self>::GetRuntimeClassName,
+        AsyncBox::<Self>::GetTrustLevel,
+        AsyncBox::<Self>::SetProgress,
+        AsyncBox::<Self>::GetProgress,
+        AsyncBox::<Self>::SetCompleted,
+        AsyncBox::<Self>::GetCompleted,
+        AsyncBox::<Self>::GetResultsValue::<T::Abi>,
+        PhantomData,
+        PhantomData,
+    );
+}
+
+/// Drives the future behind an async object created by one of the `from_future` constructors and
+/// must be spawned on an executor, or otherwise polled to completion.
+///
+/// The async object completes with the future's output. If the async object is canceled the
+/// future is dropped without being polled again and the task completes. If the task is dropped
+/// before the future completes the async object is canceled.
+pub struct AsyncTask<K: AsyncKind, F> {
+    object: *mut AsyncBox<K>,
+    future: Option<Pin<Box<F>>>,
+}
+
+// The async object is agile and its handlers are called on whichever thread completes it.
+unsafe impl<K: AsyncKind, F: Send> Send for AsyncTask<K, F> where K::Output: Send {}
+
+impl<K: AsyncKind, F: Future<Output = Result<K::Output>>> Future for AsyncTask<K, F> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let object = unsafe { &*this.object };
+
+        {
+            let mut state = object.state.lock().unwrap();
+
+            if state.status != AsyncStatus::Started {
+                this.future = None;
+                return Poll::Ready(());
+            }
+
+            state.waker = Some(context.waker().clone());
+        }
+
+        let result = match &mut this.future {
+            Some(future) => match future.as_mut().poll(context) {
+                Poll::Ready(result) => result,
+                Poll::Pending => return Poll::Pending,
+            },
+            None => return Poll::Ready(()),
+        };
+
+        this.future = None;
+        object.complete(result);
+        Poll::Ready(())
+    }
+}
+
+impl<K: AsyncKind, F> Drop for AsyncTask<K, F> {
+    fn drop(&mut self) {
+        unsafe {
+            if self.future.take().is_some() {
+                (*self.object).cancel();
+            }
+
+            AsyncBox::release(self.object);
+        }
+    }
+}
+
+impl<K: AsyncKind, F> std::fmt::Debug for AsyncTask<K, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AsyncTask({})", K::NAME)
+    }
+}
+
+/// Reports progress from the future behind an async object with progress to the object's
+/// progress handler, if any.
+pub struct AsyncProgressReporter<P: RuntimeType> {
+    object: IUnknown,
+    handler: fn(&IUnknown) -> Option<IUnknown>,
+    phantom: PhantomData<P>,
+}
+
+// The async object is agile and progress handlers are called on whichever thread reports progress.
+unsafe impl<P: RuntimeType> Send for AsyncProgressReporter<P> {}
+unsafe impl<P: RuntimeType> Sync for AsyncProgressReporter<P> {}
+
+impl<P: RuntimeType> AsyncProgressReporter<P> {
+    /// Calls the progress handler with `value`. Failures reported by the handler are ignored.
+    pub fn report(&self, value: &P::DefaultType) {
+        if let Some(handler) = (self.handler)(&self.object) {
+            unsafe {
+                let vtable = *(handler.abi() as *const *const ProgressHandler_abi<P::Abi>);
+                let _ = ((*vtable).3)(
+                    handler.abi(),
+                    self.object.abi(),
+                    std::mem::transmute_copy(value),
+                );
+            }
+        }
+    }
+}
+
+impl<P: RuntimeType> Clone for AsyncProgressReporter<P> {
+    fn clone(&self) -> Self {
+        Self {
+            object: self.object.clone(),
+            handler: self.handler,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: RuntimeType> std::fmt::Debug for AsyncProgressReporter<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AsyncProgressReporter({:?})", self.object)
+    }
+}
+
+// The layouts of the completed and progress handler delegates, which differ only in their type
+// parameters and are therefore called through these rather than the generated bindings.
+#[repr(C)]
+struct CompletedHandler_abi(
+    unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr, info: RawPtr, status: AsyncStatus) -> ErrorCode,
+);
+
+#[repr(C)]
+struct ProgressHandler_abi<A>(
+    unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr, info: RawPtr, value: A) -> ErrorCode,
+);
+
+#[repr(C)]
+struct AsyncBox<K: AsyncKind> {
+    vtable: *const <K::Interface as Interface>::Vtable,
+    info_vtable: *const IAsyncInfo_abi,
+    count: RefCount,
+    id: u32,
+    state: Mutex<AsyncObjectState<K>>,
+}
+
+struct AsyncObjectState<K: AsyncKind> {
+    status: AsyncStatus,
+    error_code: ErrorCode,
+    output: Option<K::Output>,
+    completed: Option<IUnknown>,
+    completed_assigned: bool,
+    progress: Option<IUnknown>,
+    waker: Option<Waker>,
+}
+
+#[allow(non_snake_case)]
+impl<K: AsyncKind> AsyncBox<K> {
+    const INFO_VTABLE: IAsyncInfo_abi = IAsyncInfo_abi(
+        Self::InfoQueryInterface,
+        Self::InfoAddRef,
+        Self::InfoRelease,
+        Self::GetIids,
+        Self::GetRuntimeClassName,
+        Self::GetTrustLevel,
+        Self::GetId,
+        Self::GetStatus,
+        Self::GetErrorCode,
+        Self::Cancel,
+        Self::Close,
+    );
+
+    fn new() -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            vtable: K::VTABLE,
+            info_vtable: &Self::INFO_VTABLE,
+            count: RefCount::new(),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            state: Mutex::new(AsyncObjectState {
+                status: AsyncStatus::Started,
+                error_code: ErrorCode::S_OK,
+                output: None,
+                completed: None,
+                completed_assigned: false,
+                progress: None,
+                waker: None,
+            }),
+        }))
+    }
+
+    // Returns the interface, which owns the object's initial reference, and the task that holds
+    // another reference.
+    unsafe fn create_task<F>(object: *mut Self, future: F) -> (K::Interface, AsyncTask<K, F>) {
+        (*object).count.add_ref();
+
+        (
+            std::mem::transmute_copy(&(*object).this()),
+            AsyncTask {
+                object,
+                future: Some(Box::pin(future)),
+            },
+        )
+    }
+
+    fn create<F>(future: F) -> (K::Interface, AsyncTask<K, F>) {
+        unsafe { Self::create_task(Self::new(), future) }
+    }
+
+    fn create_with_progress<P: RuntimeType, F, S>(start: S) -> (K::Interface, AsyncTask<K, F>)
+    where
+        S: FnOnce(AsyncProgressReporter<P>) -> F,
+    {
+        unsafe {
+            let object = Self::new();
+            (*object).count.add_ref();
+
+            let reporter = AsyncProgressReporter {
+                object: std::mem::transmute((*object).this()),
+                handler: Self::progress_handler,
+                phantom: PhantomData,
+            };
+
+            Self::create_task(object, start(reporter))
+        }
+    }
+
+    fn this(&self) -> RawPtr {
+        &self.vtable as *const _ as _
+    }
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    unsafe fn from_info<'a>(this: RawPtr) -> &'a Self {
+        &*((this as *mut RawPtr).sub(1) as *const Self)
+    }
+
+    unsafe fn release(this: *mut Self) -> u32 {
+        let remaining = (*this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this));
+        }
+
+        remaining
+    }
+
+    fn progress_handler(object: &IUnknown) -> Option<IUnknown> {
+        unsafe {
+            let this = Self::from_this(object.abi());
+            let state = this.state.lock().unwrap();
+
+            if state.status == AsyncStatus::Started {
+                state.progress.clone()
+            } else {
+                None
+            }
+        }
+    }
+
+    fn complete(&self, result: Result<K::Output>) {
+        let (handler, status) = {
+            let mut state = self.state.lock().unwrap();
+
+            if state.status != AsyncStatus::Started {
+                return;
+            }
+
+            match result {
+                Ok(output) => {
+                    state.output = Some(output);
+                    state.status = AsyncStatus::Completed;
+                }
+                Err(error) => {
+                    let code = error.code();
+                    state.error_code = if code.is_err() {
+                        code
+                    } else {
+                        ErrorCode::E_FAIL
+                    };
+                    state.status = AsyncStatus::Error;
+                }
+            }
+
+            (state.completed.take(), state.status)
+        };
+
+        self.invoke_completed(handler, status);
+    }
+
+    fn cancel(&self) {
+        let (handler, waker) = {
+            let mut state = self.state.lock().unwrap();
+
+            if state.status != AsyncStatus::Started {
+                return;
+            }
+
+            state.status = AsyncStatus::Canceled;
+            state.error_code = ErrorCode::ERROR_CANCELLED;
+            state.progress = None;
+            (state.completed.take(), state.waker.take())
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        self.invoke_completed(handler, AsyncStatus::Canceled);
+    }
+
+    fn invoke_completed(&self, handler: Option<IUnknown>, status: AsyncStatus) {
+        if let Some(handler) = handler {
+            unsafe {
+                let vtable = *(handler.abi() as *const *const CompletedHandler_abi);
+                let _ = ((*vtable).3)(handler.abi(), self.this(), status);
+            }
+        }
+    }
+
+    fn results(&self) -> std::result::Result<K::Output, ErrorCode> {
+        let state = self.state.lock().unwrap();
+
+        if state.status == AsyncStatus::Completed {
+            Ok(state.output.clone().unwrap())
+        } else if state.status == AsyncStatus::Started {
+            Err(E_ILLEGAL_METHOD_CALL)
+        } else {
+            Err(state.error_code)
+        }
+    }
+
+    fn query(&self, iid: &Guid, interface: *mut RawPtr) -> ErrorCode {
+        unsafe {
+            *interface = if iid == &<K::Interface as Interface>::IID
+                || iid == &<IUnknown as Interface>::IID
+                || iid == &<Object as Interface>::IID
+                || iid == &<IAgileObject as Interface>::IID
+            {
+                self.this()
+            } else if iid == &<IAsyncInfo as Interface>::IID {
+                &self.info_vtable as *const _ as _
+            } else {
+                std::ptr::null_mut()
+            };
+
+            if (*interface).is_null() {
+                ErrorCode::E_NOINTERFACE
+            } else {
+                self.count.add_ref();
+                ErrorCode::S_OK
+            }
+        }
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        Self::from_this(this).query(iid, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        Self::release(this as *mut Self)
+    }
+
+    unsafe extern "system" fn InfoQueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        Self::from_info(this).query(iid, interface)
+    }
+
+    unsafe extern "system" fn InfoAddRef(this: RawPtr) -> u32 {
+        Self::from_info(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn InfoRelease(this: RawPtr) -> u32 {
+        Self::release((this as *mut RawPtr).sub(1) as *mut Self)
+    }
+
+    unsafe extern "system" fn GetIids(
+        _: RawPtr,
+        count: *mut u32,
+        values: *mut *mut Guid,
+    ) -> ErrorCode {
+        *count = 0;
+        *values = std::ptr::null_mut();
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        let name: HString = K::NAME.into();
+        *value = std::mem::transmute(name);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetTrustLevel(_: RawPtr, value: *mut i32) -> ErrorCode {
+        *value = 0;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn SetCompleted(this: RawPtr, handler: RawPtr) -> ErrorCode {
+        let this = Self::from_this(this);
+        let handler = (*(&handler as *const RawPtr as *const Option<IUnknown>)).clone();
+
+        let status = {
+            let mut state = this.state.lock().unwrap();
+
+            if state.completed_assigned {
+                return E_ILLEGAL_DELEGATE_ASSIGNMENT;
+            }
+
+            state.completed_assigned = true;
+
+            if state.status == AsyncStatus::Started {
+                state.completed = handler;
+                return ErrorCode::S_OK;
+            }
+
+            state.status
+        };
+
+        // The handler is called immediately if the object has already completed.
+        this.invoke_completed(handler, status);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetCompleted(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let handler = Self::from_this(this)
+            .state
+            .lock()
+            .unwrap()
+            .completed
+            .clone();
+        *result = std::mem::transmute(handler);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn SetProgress(this: RawPtr, handler: RawPtr) -> ErrorCode {
+        let handler = (*(&handler as *const RawPtr as *const Option<IUnknown>)).clone();
+        Self::from_this(this).state.lock().unwrap().progress = handler;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetProgress(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let handler = Self::from_this(this).state.lock().unwrap().progress.clone();
+        *result = std::mem::transmute(handler);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetResults(this: RawPtr) -> ErrorCode {
+        match Self::from_this(this).results() {
+            Ok(_) => ErrorCode::S_OK,
+            Err(code) => code,
+        }
+    }
+
+    unsafe extern "system" fn GetResultsValue<A>(this: RawPtr, result: *mut A) -> ErrorCode {
+        match Self::from_this(this).results() {
+            Ok(output) => {
+                std::ptr::write(result, std::mem::transmute_copy(&output));
+                std::mem::forget(output);
+                ErrorCode::S_OK
+            }
+            Err(code) => code,
+        }
+    }
+
+    unsafe extern "system" fn GetId(this: RawPtr, result: *mut u32) -> ErrorCode {
+        *result = Self::from_info(this).id;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetStatus(this: RawPtr, result: *mut AsyncStatus) -> ErrorCode {
+        *result = Self::from_info(this).state.lock().unwrap().status;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetErrorCode(this: RawPtr, result: *mut ErrorCode) -> ErrorCode {
+        *result = Self::from_info(this).state.lock().unwrap().error_code;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn Cancel(this: RawPtr) -> ErrorCode {
+        Self::from_info(this).cancel();
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn Close(this: RawPtr) -> ErrorCode {
+        if Self::from_info(this).state.lock().unwrap().status == AsyncStatus::Started {
+            E_ILLEGAL_STATE_CHANGE
+        } else {
+            ErrorCode::S_OK
+        }
+    }
+}
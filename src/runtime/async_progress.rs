@@ -0,0 +1,96 @@
+use crate::*;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// A stream of the progress updates reported by a WinRT async operation with progress, returned
+/// alongside the operation's [`AsyncFuture`] by the generated `with_progress` method.
+///
+/// Updates are buffered until they are polled. The stream ends once the operation completes and
+/// any buffered updates have been taken.
+pub struct AsyncProgress<P> {
+    completion: Arc<Mutex<AsyncState>>,
+    queue: Arc<Mutex<ProgressQueue<P>>>,
+}
+
+struct ProgressQueue<P> {
+    items: VecDeque<P>,
+    waker: Option<Waker>,
+}
+
+impl<T: Async> AsyncFuture<T> {
+    /// Registers the completion handler immediately so that the stream ends even if the future
+    /// isn't polled, returning the stream along with a function that reports each progress update
+    /// to it. This is used by the generated bindings and should not be used directly.
+    #[doc(hidden)]
+    pub fn progress<P: 'static>(&mut self) -> Result<(AsyncProgress<P>, impl FnMut(P) + 'static)> {
+        let completion = self.register()?;
+
+        let queue = Arc::new(Mutex::new(ProgressQueue {
+            items: VecDeque::new(),
+            waker: None,
+        }));
+
+        let report_queue = queue.clone();
+
+        let report = move |value| {
+            let waker = {
+                let mut queue = report_queue.lock().unwrap();
+                queue.items.push_back(value);
+                queue.waker.take()
+            };
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        };
+
+        Ok((AsyncProgress { completion, queue }, report))
+    }
+}
+
+impl<P> AsyncProgress<P> {
+    /// Attempts to take the next progress update, registering the current task to be woken when
+    /// an update is reported or the operation completes if none are buffered.
+    pub fn poll_next_progress(&mut self, context: &mut Context) -> Poll<Option<P>> {
+        {
+            let mut queue = self.queue.lock().unwrap();
+
+            if let Some(value) = queue.items.pop_front() {
+                return Poll::Ready(Some(value));
+            }
+
+            queue.waker = Some(context.waker().clone());
+        }
+
+        {
+            let mut completion = self.completion.lock().unwrap();
+
+            if !completion.completed {
+                completion.progress_waker = Some(context.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        // An update may have been reported just before the operation completed.
+        Poll::Ready(self.queue.lock().unwrap().items.pop_front())
+    }
+}
+
+impl<P> Unpin for AsyncProgress<P> {}
+
+impl<P> futures_core::Stream for AsyncProgress<P> {
+    type Item = P;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_progress(context)
+    }
+}
+
+impl<P> std::fmt::Debug for AsyncProgress<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let queue = self.queue.lock().unwrap();
+        write!(f, "AsyncProgress({} buffered)", queue.items.len())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::*;
+use std::collections::VecDeque;
+
+/// The number of items retrieved by each `GetMany` call made by the collection iterators, unless
+/// they are given another chunk size.
+pub const DEFAULT_CHUNK_SIZE: u32 = 64;
+
+/// Retrieves up to `count` items with a collection's `GetMany` method, which is called with a
+/// buffer of `count` items. This is used by the generated collection iterators and should not be
+/// used directly.
+pub fn get_chunk<T: RuntimeType, F>(count: u32, get_many: F) -> Result<VecDeque<T>>
+where
+    F: FnOnce(&mut [T::DefaultType]) -> Result<u32>,
+{
+    // `GetMany` overwrites the items it retrieves without releasing them so the buffer starts out
+    // with default values, whose ABI representation is all zeros for every WinRT type.
+    let mut items: Vec<T::DefaultType> =
+        (0..count).map(|_| unsafe { std::mem::zeroed() }).collect();
+
+    let retrieved = get_many(&mut items)?;
+    items.truncate(retrieved as usize);
+
+    items
+        .into_iter()
+        .map(|item| unsafe {
+            let abi = std::mem::transmute_copy(&item);
+            std::mem::forget(item);
+            T::from_abi(abi)
+        })
+        .collect()
+}
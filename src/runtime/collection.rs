@@ -0,0 +1,308 @@
+use crate::foundation::collections::{
+    CollectionChange, IMapChangedEventArgs, IMapChangedEventArgs_abi, IVectorChangedEventArgs,
+    IVectorChangedEventArgs_abi,
+};
+use crate::foundation::EventRegistrationToken;
+use crate::*;
+
+// The error code returned by an iterator once its collection has changed.
+pub(crate) const E_CHANGED_STATE: ErrorCode = ErrorCode(0x8000_000C);
+
+/// Borrows a value passed to a collection method as its default type without taking ownership.
+pub(crate) unsafe fn abi_ref<T: RuntimeType>(value: &T::Abi) -> &T::DefaultType {
+    &*(value as *const T::Abi as *const T::DefaultType)
+}
+
+/// Returns a clone of `value` through a collection method's out parameter.
+pub(crate) unsafe fn write_abi<T: RuntimeType>(result: *mut T::Abi, value: &T::DefaultType)
+where
+    T::DefaultType: Clone,
+{
+    let value = value.clone();
+    std::ptr::write(result, std::mem::transmute_copy(&value));
+    std::mem::forget(value);
+}
+
+/// Completes a `QueryInterface` call on a Rust-backed collection object, where `found` is the
+/// requested interface or null if the object doesn't implement it.
+pub(crate) unsafe fn query_result(
+    count: &RefCount,
+    found: RawPtr,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    *interface = found;
+
+    if found.is_null() {
+        ErrorCode::E_NOINTERFACE
+    } else {
+        count.add_ref();
+        ErrorCode::S_OK
+    }
+}
+
+pub(crate) unsafe fn runtime_class_name(name: &str, value: *mut RawPtr) -> ErrorCode {
+    let name: HString = name.into();
+    *value = std::mem::transmute(name);
+    ErrorCode::S_OK
+}
+
+#[allow(non_snake_case)]
+pub(crate) unsafe extern "system" fn GetIids(
+    _: RawPtr,
+    count: *mut u32,
+    values: *mut *mut Guid,
+) -> ErrorCode {
+    *count = 0;
+    *values = std::ptr::null_mut();
+    ErrorCode::S_OK
+}
+
+#[allow(non_snake_case)]
+pub(crate) unsafe extern "system" fn GetTrustLevel(_: RawPtr, value: *mut i32) -> ErrorCode {
+    *value = 0;
+    ErrorCode::S_OK
+}
+
+// The layout shared by the `VectorChangedEventHandler` and `MapChangedEventHandler` delegates,
+// which are therefore called through this rather than the generated bindings.
+#[repr(C)]
+struct ChangedHandler_abi(
+    unsafe extern "system" fn(this: RawPtr, iid: &Guid, interface: *mut RawPtr) -> ErrorCode,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr) -> u32,
+    unsafe extern "system" fn(this: RawPtr, sender: RawPtr, args: RawPtr) -> ErrorCode,
+);
+
+/// The change handlers registered with an observable Rust-backed collection.
+#[derive(Default)]
+pub(crate) struct ChangedHandlers {
+    next: i64,
+    handlers: Vec<(i64, IUnknown)>,
+}
+
+impl ChangedHandlers {
+    pub unsafe fn add(&mut self, handler: RawPtr, token: *mut EventRegistrationToken) -> ErrorCode {
+        match &*(&handler as *const RawPtr as *const Option<IUnknown>) {
+            Some(handler) => {
+                self.next += 1;
+                self.handlers.push((self.next, handler.clone()));
+                *token = EventRegistrationToken { value: self.next };
+                ErrorCode::S_OK
+            }
+            None => ErrorCode::E_POINTER,
+        }
+    }
+
+    pub fn remove(&mut self, token: EventRegistrationToken) -> ErrorCode {
+        self.handlers.retain(|(value, _)| *value != token.value);
+        ErrorCode::S_OK
+    }
+
+    /// Returns the handlers to call, which must be called after the collection's lock is released
+    /// since a handler may well access the collection.
+    pub fn snapshot(&self) -> Vec<IUnknown> {
+        self.handlers
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect()
+    }
+}
+
+/// Calls each of `handlers` with the collection's observable interface and the change event
+/// arguments. Failures reported by the handlers are ignored.
+pub(crate) fn invoke_changed(handlers: Vec<IUnknown>, sender: RawPtr, args: &IUnknown) {
+    for handler in handlers {
+        unsafe {
+            let vtable = *(handler.abi() as *const *const ChangedHandler_abi);
+            let _ = ((*vtable).3)(handler.abi(), sender, args.abi());
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct VectorChangedEventArgs {
+    vtable: *const IVectorChangedEventArgs_abi,
+    count: RefCount,
+    change: CollectionChange,
+    index: u32,
+}
+
+#[allow(non_snake_case)]
+impl VectorChangedEventArgs {
+    const VTABLE: IVectorChangedEventArgs_abi = IVectorChangedEventArgs_abi(
+        Self::QueryInterface,
+        Self::AddRef,
+        Self::Release,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetCollectionChange,
+        Self::GetIndex,
+    );
+
+    pub fn create(change: CollectionChange, index: u32) -> IUnknown {
+        let this = Box::into_raw(Box::new(Self {
+            vtable: &Self::VTABLE,
+            count: RefCount::new(),
+            change,
+            index,
+        }));
+
+        unsafe { std::mem::transmute(this) }
+    }
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let found = if iid == &<IVectorChangedEventArgs as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+            || iid == &<IAgileObject as Interface>::IID
+        {
+            this
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&Self::from_this(this).count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        let remaining = Self::from_this(this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut Self));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name(
+            "Windows.Foundation.Collections.IVectorChangedEventArgs",
+            value,
+        )
+    }
+
+    unsafe extern "system" fn GetCollectionChange(
+        this: RawPtr,
+        result: *mut CollectionChange,
+    ) -> ErrorCode {
+        *result = Self::from_this(this).change;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetIndex(this: RawPtr, result: *mut u32) -> ErrorCode {
+        *result = Self::from_this(this).index;
+        ErrorCode::S_OK
+    }
+}
+
+#[repr(C)]
+pub(crate) struct MapChangedEventArgs<K: RuntimeType + 'static> {
+    vtable: *const IMapChangedEventArgs_abi<K>,
+    count: RefCount,
+    change: CollectionChange,
+    key: Option<K::DefaultType>,
+}
+
+#[allow(non_snake_case)]
+impl<K: RuntimeType + 'static> MapChangedEventArgs<K>
+where
+    K::DefaultType: Clone,
+{
+    const VTABLE: &'static IMapChangedEventArgs_abi<K> = &IMapChangedEventArgs_abi(
+        Self::QueryInterface,
+        Self::AddRef,
+        Self::Release,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetCollectionChange,
+        Self::GetKey,
+        std::marker::PhantomData,
+    );
+
+    /// The `key` is `None` only for a `Reset`, in which case the event reports the key type's
+    /// default value.
+    pub fn create(change: CollectionChange, key: Option<K::DefaultType>) -> IUnknown {
+        let this = Box::into_raw(Box::new(Self {
+            vtable: Self::VTABLE,
+            count: RefCount::new(),
+            change,
+            key,
+        }));
+
+        unsafe { std::mem::transmute(this) }
+    }
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let found = if iid == &<IMapChangedEventArgs<K> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&Self::from_this(this).count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        let remaining = Self::from_this(this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut Self));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name(
+            "Windows.Foundation.Collections.IMapChangedEventArgs`1",
+            value,
+        )
+    }
+
+    unsafe extern "system" fn GetCollectionChange(
+        this: RawPtr,
+        result: *mut CollectionChange,
+    ) -> ErrorCode {
+        *result = Self::from_this(this).change;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetKey(this: RawPtr, result: *mut K::Abi) -> ErrorCode {
+        match &Self::from_this(this).key {
+            Some(key) => write_abi::<K>(result, key),
+            // The ABI representation of every WinRT type's default value is all zeros.
+            None => std::ptr::write(result, std::mem::zeroed()),
+        }
+
+        ErrorCode::S_OK
+    }
+}
@@ -0,0 +1,87 @@
+use crate::foundation::EventRegistrationToken;
+use crate::*;
+
+/// Revokes an event handler when dropped. An `EventRevoker` is returned by the generated `on_*`
+/// methods, which register a closure as the event's handler, and calls the event's matching
+/// `remove_*` method. A handler that is already a delegate can be registered with the event's `add`
+/// method and given to [`EventRevoker::new`] instead. If the event source supports weak references
+/// then the revoker doesn't keep the source alive and, should the source be destroyed first, there
+/// is nothing left to revoke. Otherwise the revoker holds a strong reference.
+pub struct EventRevoker<T: Interface> {
+    source: Option<EventSource<T>>,
+    token: EventRegistrationToken,
+    revoke: fn(&T, EventRegistrationToken) -> Result<()>,
+}
+
+enum EventSource<T> {
+    Weak(IWeakReference),
+    Strong(T),
+}
+
+impl<T: Interface + Clone> EventRevoker<T> {
+    /// Creates a revoker for the event handler registered with `source` and identified by `token`.
+    /// The `revoke` function is called with the source and token to remove the handler.
+    pub fn new(
+        source: &T,
+        token: EventRegistrationToken,
+        revoke: fn(&T, EventRegistrationToken) -> Result<()>,
+    ) -> Self {
+        let source = match source
+            .cast::<IWeakReferenceSource>()
+            .and_then(|source| source.weak_reference())
+        {
+            Ok(weak) => EventSource::Weak(weak),
+            Err(_) => EventSource::Strong(source.clone()),
+        };
+
+        Self {
+            source: Some(source),
+            token,
+            revoke,
+        }
+    }
+}
+
+impl<T: Interface> EventRevoker<T> {
+    /// Returns the token identifying the event handler.
+    pub fn token(&self) -> EventRegistrationToken {
+        self.token
+    }
+
+    /// Revokes the event handler immediately rather than when the revoker is dropped.
+    pub fn revoke(mut self) -> Result<()> {
+        self.revoke_source()
+    }
+
+    /// Consumes the revoker without revoking the event handler, which then remains registered
+    /// for as long as the source is alive.
+    pub fn forget(mut self) -> EventRegistrationToken {
+        self.source = None;
+        self.token
+    }
+
+    fn revoke_source(&mut self) -> Result<()> {
+        match self.source.take() {
+            Some(EventSource::Weak(weak)) => match weak.resolve::<T>() {
+                Some(source) => (self.revoke)(&source, self.token),
+                None => Ok(()),
+            },
+            Some(EventSource::Strong(source)) => (self.revoke)(&source, self.token),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Interface> Drop for EventRevoker<T> {
+    fn drop(&mut self) {
+        // There is no way to report an error from `drop` and a failure to revoke generally means
+        // that the source is being torn down anyway.
+        let _ = self.revoke_source();
+    }
+}
+
+impl<T: Interface> std::fmt::Debug for EventRevoker<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EventRevoker({:?})", self.token)
+    }
+}
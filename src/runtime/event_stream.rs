@@ -0,0 +1,199 @@
+use crate::foundation::{EventHandler, EventRegistrationToken, TypedEventHandler};
+use crate::*;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// What an [`EventStream`] does with an event that is raised while its buffer is full.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Discard the oldest buffered event to make room for the new one.
+    DropOldest,
+    /// Discard the new event.
+    DropNewest,
+}
+
+/// A stream of the invocations of an event, allowing an event to be consumed from async code with
+/// `while let Some((sender, args)) = events.next().await` rather than with a closure. Events are
+/// buffered until they are polled, up to a fixed capacity after which the [`Overflow`] policy
+/// decides which events are discarded. The handler is removed when the stream is dropped.
+///
+/// An event source never signals that it won't raise any more events so the stream doesn't end.
+pub struct EventStream<S: Interface, T> {
+    queue: Arc<Mutex<EventQueue<T>>>,
+    _revoker: EventRevoker<S>,
+}
+
+struct EventQueue<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    overflow: Overflow,
+    dropped: usize,
+    waker: Option<Waker>,
+}
+
+impl<T> EventQueue<T> {
+    // Returns the waker of the task waiting for an event, which is only woken once the lock is
+    // released since an executor may poll the task from within `wake`.
+    fn push(&mut self, item: T) -> Option<Waker> {
+        if self.items.len() == self.capacity {
+            self.dropped += 1;
+
+            match self.overflow {
+                Overflow::DropOldest => {
+                    self.items.pop_front();
+                }
+                Overflow::DropNewest => return None,
+            }
+        }
+
+        self.items.push_back(item);
+        self.waker.take()
+    }
+}
+
+impl<S: Interface + Clone, A: Clone + 'static, B: Clone + 'static> EventStream<S, (A, B)> {
+    /// Subscribes to an event with a `TypedEventHandler` using the event's generated `add` and
+    /// `remove` methods, as in
+    /// `EventStream::typed(&source, 16, Overflow::DropOldest, |s, h| s.closed(h), |s, t| s.remove_closed(t))`.
+    pub fn typed<TSender, TResult>(
+        source: &S,
+        capacity: usize,
+        overflow: Overflow,
+        add: fn(&S, &TypedEventHandler<TSender, TResult>) -> Result<EventRegistrationToken>,
+        remove: fn(&S, EventRegistrationToken) -> Result<()>,
+    ) -> Result<Self>
+    where
+        TSender: RuntimeType<DefaultType = A> + 'static,
+        TResult: RuntimeType<DefaultType = B> + 'static,
+    {
+        let queue = EventStream::<S, (A, B)>::queue(capacity, overflow);
+        let handler_queue = queue.clone();
+
+        let handler = TypedEventHandler::<TSender, TResult>::new(move |sender, args| {
+            let waker = handler_queue
+                .lock()
+                .unwrap()
+                .push((sender.clone(), args.clone()));
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+
+            Ok(())
+        });
+
+        let token = add(source, &handler)?;
+
+        Ok(Self {
+            queue,
+            _revoker: EventRevoker::new(source, token, remove),
+        })
+    }
+}
+
+impl<S: Interface + Clone, B: Clone + 'static> EventStream<S, (Option<Object>, B)> {
+    /// Subscribes to an event with an `EventHandler` using the event's generated `add` and
+    /// `remove` methods. See [`EventStream::typed`].
+    pub fn untyped<T>(
+        source: &S,
+        capacity: usize,
+        overflow: Overflow,
+        add: fn(&S, &EventHandler<T>) -> Result<EventRegistrationToken>,
+        remove: fn(&S, EventRegistrationToken) -> Result<()>,
+    ) -> Result<Self>
+    where
+        T: RuntimeType<DefaultType = B> + 'static,
+    {
+        let queue = EventStream::<S, (Option<Object>, B)>::queue(capacity, overflow);
+        let handler_queue = queue.clone();
+
+        let handler = EventHandler::<T>::new(move |sender, args| {
+            let waker = handler_queue
+                .lock()
+                .unwrap()
+                .push((sender.clone(), args.clone()));
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+
+            Ok(())
+        });
+
+        let token = add(source, &handler)?;
+
+        Ok(Self {
+            queue,
+            _revoker: EventRevoker::new(source, token, remove),
+        })
+    }
+}
+
+impl<S: Interface, T> EventStream<S, T> {
+    fn queue(capacity: usize, overflow: Overflow) -> Arc<Mutex<EventQueue<T>>> {
+        assert!(capacity > 0, "An EventStream requires a non-zero capacity");
+
+        Arc::new(Mutex::new(EventQueue {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            overflow,
+            dropped: 0,
+            waker: None,
+        }))
+    }
+
+    /// Returns the number of events discarded so far because the buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.queue.lock().unwrap().dropped
+    }
+
+    /// Returns a future that resolves to the next event.
+    pub fn next(&mut self) -> EventStreamNext<'_, S, T> {
+        EventStreamNext(self)
+    }
+
+    /// Attempts to take the next buffered event, registering the current task to be woken when
+    /// an event is raised if none are buffered.
+    pub fn poll_next_event(&mut self, context: &mut Context) -> Poll<Option<T>> {
+        let mut queue = self.queue.lock().unwrap();
+
+        match queue.items.pop_front() {
+            Some(item) => Poll::Ready(Some(item)),
+            None => {
+                queue.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// Nothing is pinned structurally since the buffer is shared with the handler through an `Arc`.
+impl<S: Interface, T> Unpin for EventStream<S, T> {}
+
+impl<S: Interface, T> futures_core::Stream for EventStream<S, T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_event(context)
+    }
+}
+
+impl<S: Interface, T> std::fmt::Debug for EventStream<S, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EventStream({:?})", self._revoker)
+    }
+}
+
+/// The future returned by [`EventStream::next`].
+pub struct EventStreamNext<'a, S: Interface, T>(&'a mut EventStream<S, T>);
+
+impl<'a, S: Interface, T> Future for EventStreamNext<'a, S, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        self.get_mut().0.poll_next_event(context)
+    }
+}
@@ -0,0 +1,846 @@
+use super::collection::*;
+use crate::foundation::collections::{
+    CollectionChange, IIterable, IIterable_abi, IIterator, IIterator_abi, IKeyValuePair,
+    IKeyValuePair_abi, IMap, IMapView, IMapView_abi, IMap_abi, IObservableMap, IObservableMap_abi,
+};
+use crate::foundation::EventRegistrationToken;
+use crate::*;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+/// The operations a Rust-backed map needs from its underlying Rust map.
+trait MapStorage<K, V>: 'static {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn contains(&self, key: &K) -> bool;
+    // Returns true if an existing value was replaced.
+    fn insert(&mut self, key: K, value: V) -> bool;
+    fn remove(&mut self, key: &K) -> bool;
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    fn pairs(&self) -> Vec<(K, V)>;
+}
+
+impl<K, V, S> MapStorage<K, V> for HashMap<K, V, S>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+    S: BuildHasher + 'static,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> bool {
+        HashMap::insert(self, key, value).is_some()
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        HashMap::remove(self, key).is_some()
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn pairs(&self) -> Vec<(K, V)> {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<K, V> MapStorage<K, V> for BTreeMap<K, V>
+where
+    K: Ord + Clone + 'static,
+    V: Clone + 'static,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> bool {
+        BTreeMap::insert(self, key, value).is_some()
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        BTreeMap::remove(self, key).is_some()
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn pairs(&self) -> Vec<(K, V)> {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+macro_rules! map_conversions {
+    ($storage:ty, [$($generics:tt)*], [$($bounds:tt)*]) => {
+        impl<K: RuntimeType + 'static, V: RuntimeType + 'static, $($generics)*> From<$storage> for IMap<K, V>
+        where
+            $($bounds)*
+        {
+            fn from(values: $storage) -> Self {
+                MapBox::<K, V, $storage>::create(values, MAP)
+            }
+        }
+
+        impl<K: RuntimeType + 'static, V: RuntimeType + 'static, $($generics)*> From<$storage> for IMapView<K, V>
+        where
+            $($bounds)*
+        {
+            fn from(values: $storage) -> Self {
+                MapViewBox::<K, V, $storage>::create(MapBox::new(values), VIEW_VIEW)
+            }
+        }
+
+        impl<K: RuntimeType + 'static, V: RuntimeType + 'static, $($generics)*> From<$storage> for IIterable<IKeyValuePair<K, V>>
+        where
+            $($bounds)*
+        {
+            fn from(values: $storage) -> Self {
+                MapViewBox::<K, V, $storage>::create(MapBox::new(values), VIEW_ITERABLE)
+            }
+        }
+
+        impl<K: RuntimeType + 'static, V: RuntimeType + 'static, $($generics)*> From<$storage> for IObservableMap<K, V>
+        where
+            $($bounds)*
+        {
+            fn from(values: $storage) -> Self {
+                MapBox::<K, V, $storage>::create(values, OBSERVABLE)
+            }
+        }
+    };
+}
+
+map_conversions!(
+    HashMap<K::DefaultType, V::DefaultType, S>,
+    [S: BuildHasher + 'static],
+    [K::DefaultType: Hash + Eq + Clone, V::DefaultType: Clone]
+);
+
+map_conversions!(
+    BTreeMap<K::DefaultType, V::DefaultType>,
+    [],
+    [K::DefaultType: Ord + Clone, V::DefaultType: Clone]
+);
+
+// A Rust-backed map implementing `IMap<K, V>`, `IMapView<K, V>`, `IIterable<IKeyValuePair<K, V>>`
+// and `IObservableMap<K, V>`, created by converting a `HashMap` or `BTreeMap` into `IMap<K, V>` or
+// `IObservableMap<K, V>`. A map converted into one of the read-only interfaces is wrapped in a
+// `MapViewBox`. The interfaces are laid out as with the Rust-backed vector.
+#[repr(C)]
+struct MapBox<K: RuntimeType + 'static, V: RuntimeType + 'static, M> {
+    map_vtable: *const IMap_abi<K, V>,
+    view_vtable: *const IMapView_abi<K, V>,
+    iterable_vtable: *const IIterable_abi<IKeyValuePair<K, V>>,
+    observable_vtable: *const IObservableMap_abi<K, V>,
+    count: RefCount,
+    state: Mutex<MapState<M>>,
+}
+
+struct MapState<M> {
+    values: M,
+    // Incremented by every change so that outstanding iterators can tell they're invalid.
+    version: u32,
+    handlers: ChangedHandlers,
+}
+
+const MAP: usize = 0;
+const VIEW: usize = 1;
+const ITERABLE: usize = 2;
+const OBSERVABLE: usize = 3;
+
+#[allow(non_snake_case)]
+impl<K: RuntimeType + 'static, V: RuntimeType + 'static, M> MapBox<K, V, M>
+where
+    K::DefaultType: Clone,
+    V::DefaultType: Clone,
+    M: MapStorage<K::DefaultType, V::DefaultType>,
+{
+    const MAP_VTABLE: &'static IMap_abi<K, V> = &IMap_abi(
+        Self::QueryInterface::<MAP>,
+        Self::AddRef::<MAP>,
+        Self::Release::<MAP>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::Lookup::<MAP>,
+        Self::GetSize::<MAP>,
+        Self::HasKey::<MAP>,
+        Self::GetView,
+        Self::Insert,
+        Self::Remove,
+        Self::Clear,
+        PhantomData,
+        PhantomData,
+    );
+
+    const VIEW_VTABLE: &'static IMapView_abi<K, V> = &IMapView_abi(
+        Self::QueryInterface::<VIEW>,
+        Self::AddRef::<VIEW>,
+        Self::Release::<VIEW>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::Lookup::<VIEW>,
+        Self::GetSize::<VIEW>,
+        Self::HasKey::<VIEW>,
+        Self::Split,
+        PhantomData,
+        PhantomData,
+    );
+
+    const ITERABLE_VTABLE: &'static IIterable_abi<IKeyValuePair<K, V>> = &IIterable_abi(
+        Self::QueryInterface::<ITERABLE>,
+        Self::AddRef::<ITERABLE>,
+        Self::Release::<ITERABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::First,
+        PhantomData,
+    );
+
+    const OBSERVABLE_VTABLE: &'static IObservableMap_abi<K, V> = &IObservableMap_abi(
+        Self::QueryInterface::<OBSERVABLE>,
+        Self::AddRef::<OBSERVABLE>,
+        Self::Release::<OBSERVABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::AddMapChanged,
+        Self::RemoveMapChanged,
+        PhantomData,
+        PhantomData,
+    );
+
+    // Returns the interface at `offset`, which owns the object's initial reference.
+    fn create<I: Interface>(values: M, offset: usize) -> I {
+        let this = Self::new(values);
+        unsafe { std::mem::transmute_copy(&(*this).interface(offset)) }
+    }
+
+    // Returns the new object, which owns its initial reference.
+    fn new(values: M) -> *const Self {
+        Box::into_raw(Box::new(Self {
+            map_vtable: Self::MAP_VTABLE,
+            view_vtable: Self::VIEW_VTABLE,
+            iterable_vtable: Self::ITERABLE_VTABLE,
+            observable_vtable: Self::OBSERVABLE_VTABLE,
+            count: RefCount::new(),
+            state: Mutex::new(MapState {
+                values,
+                version: 0,
+                handlers: ChangedHandlers::default(),
+            }),
+        }))
+    }
+
+    fn interface(&self, offset: usize) -> RawPtr {
+        unsafe { (&self.map_vtable as *const _ as *mut RawPtr).add(offset) as _ }
+    }
+
+    unsafe fn from_this<'a, const N: usize>(this: RawPtr) -> &'a Self {
+        &*((this as *mut RawPtr).sub(N) as *const Self)
+    }
+
+    // Applies a change to the map, invalidating any iterators and raising the `MapChanged` event
+    // if the change succeeds.
+    fn change<F>(&self, key: Option<K::DefaultType>, f: F) -> ErrorCode
+    where
+        F: FnOnce(&mut M) -> std::result::Result<CollectionChange, ErrorCode>,
+    {
+        let (change, handlers) = {
+            let mut state = self.state.lock().unwrap();
+
+            let change = match f(&mut state.values) {
+                Ok(change) => change,
+                Err(code) => return code,
+            };
+
+            state.version = state.version.wrapping_add(1);
+            (change, state.handlers.snapshot())
+        };
+
+        if !handlers.is_empty() {
+            let args = MapChangedEventArgs::<K>::create(change, key);
+            invoke_changed(handlers, self.interface(OBSERVABLE), &args);
+        }
+
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn QueryInterface<const N: usize>(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let this = Self::from_this::<N>(this);
+
+        let found = if iid == &<IMap<K, V> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this.interface(MAP)
+        } else if iid == &<IMapView<K, V> as Interface>::IID {
+            this.interface(VIEW)
+        } else if iid == &<IIterable<IKeyValuePair<K, V>> as Interface>::IID {
+            this.interface(ITERABLE)
+        } else if iid == &<IObservableMap<K, V> as Interface>::IID {
+            this.interface(OBSERVABLE)
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&this.count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef<const N: usize>(this: RawPtr) -> u32 {
+        Self::from_this::<N>(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release<const N: usize>(this: RawPtr) -> u32 {
+        let remaining = Self::from_this::<N>(this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw((this as *mut RawPtr).sub(N) as *mut Self));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IMap`2", value)
+    }
+
+    unsafe extern "system" fn Lookup<const N: usize>(
+        this: RawPtr,
+        key: K::Abi,
+        result: *mut V::Abi,
+    ) -> ErrorCode {
+        let state = Self::from_this::<N>(this).state.lock().unwrap();
+
+        match state.values.get(abi_ref::<K>(&key)) {
+            Some(value) => {
+                write_abi::<V>(result, value);
+                ErrorCode::S_OK
+            }
+            None => ErrorCode::E_BOUNDS,
+        }
+    }
+
+    unsafe extern "system" fn GetSize<const N: usize>(this: RawPtr, result: *mut u32) -> ErrorCode {
+        *result = Self::from_this::<N>(this)
+            .state
+            .lock()
+            .unwrap()
+            .values
+            .len() as u32;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn HasKey<const N: usize>(
+        this: RawPtr,
+        key: K::Abi,
+        result: *mut bool,
+    ) -> ErrorCode {
+        let state = Self::from_this::<N>(this).state.lock().unwrap();
+        *result = state.values.contains(abi_ref::<K>(&key));
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetView(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let this = Self::from_this::<MAP>(this);
+        this.count.add_ref();
+        *result = (*MapViewBox::new(this)).interface(VIEW_VIEW);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn Insert(
+        this: RawPtr,
+        key: K::Abi,
+        value: V::Abi,
+        result: *mut bool,
+    ) -> ErrorCode {
+        let key = abi_ref::<K>(&key).clone();
+        let value = abi_ref::<V>(&value).clone();
+        let changed_key = key.clone();
+
+        Self::from_this::<MAP>(this).change(Some(changed_key), |values| {
+            *result = values.insert(key, value);
+
+            Ok(if *result {
+                CollectionChange::ItemChanged
+            } else {
+                CollectionChange::ItemInserted
+            })
+        })
+    }
+
+    unsafe extern "system" fn Remove(this: RawPtr, key: K::Abi) -> ErrorCode {
+        let key = abi_ref::<K>(&key).clone();
+        let removed = key.clone();
+
+        Self::from_this::<MAP>(this).change(Some(key), |values| {
+            if values.remove(&removed) {
+                Ok(CollectionChange::ItemRemoved)
+            } else {
+                Err(ErrorCode::E_BOUNDS)
+            }
+        })
+    }
+
+    unsafe extern "system" fn Clear(this: RawPtr) -> ErrorCode {
+        Self::from_this::<MAP>(this).change(None, |values| {
+            values.clear();
+            Ok(CollectionChange::Reset)
+        })
+    }
+
+    // Splitting a view is optional and signalled by returning a pair of null views.
+    unsafe extern "system" fn Split(
+        _: RawPtr,
+        first: *mut RawPtr,
+        second: *mut RawPtr,
+    ) -> ErrorCode {
+        *first = std::ptr::null_mut();
+        *second = std::ptr::null_mut();
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn First(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let this = Self::from_this::<ITERABLE>(this);
+        this.count.add_ref();
+
+        let (pairs, version) = {
+            let state = this.state.lock().unwrap();
+            (state.values.pairs(), state.version)
+        };
+
+        let iterator = Box::into_raw(Box::new(MapIterator::<K, V> {
+            vtable: MapIterator::<K, V>::VTABLE,
+            count: RefCount::new(),
+            owner: this.interface(MAP),
+            version: Self::version,
+            release: Self::Release::<MAP>,
+            pairs,
+            state: Mutex::new((0, version)),
+        }));
+
+        *result = iterator as _;
+        ErrorCode::S_OK
+    }
+
+    fn version(owner: RawPtr) -> u32 {
+        unsafe { Self::from_this::<MAP>(owner).state.lock().unwrap().version }
+    }
+
+    unsafe extern "system" fn AddMapChanged(
+        this: RawPtr,
+        handler: RawPtr,
+        token: *mut EventRegistrationToken,
+    ) -> ErrorCode {
+        let this = Self::from_this::<OBSERVABLE>(this);
+        this.state.lock().unwrap().handlers.add(handler, token)
+    }
+
+    unsafe extern "system" fn RemoveMapChanged(
+        this: RawPtr,
+        token: EventRegistrationToken,
+    ) -> ErrorCode {
+        let this = Self::from_this::<OBSERVABLE>(this);
+        this.state.lock().unwrap().handlers.remove(token)
+    }
+}
+
+// A read-only view of a Rust-backed map implementing `IMapView<K, V>` and
+// `IIterable<IKeyValuePair<K, V>>`, as with the view of a Rust-backed vector.
+#[repr(C)]
+struct MapViewBox<K: RuntimeType + 'static, V: RuntimeType + 'static, M> {
+    view_vtable: *const IMapView_abi<K, V>,
+    iterable_vtable: *const IIterable_abi<IKeyValuePair<K, V>>,
+    count: RefCount,
+    owner: *const MapBox<K, V, M>,
+}
+
+const VIEW_VIEW: usize = 0;
+const VIEW_ITERABLE: usize = 1;
+
+#[allow(non_snake_case)]
+impl<K: RuntimeType + 'static, V: RuntimeType + 'static, M> MapViewBox<K, V, M>
+where
+    K::DefaultType: Clone,
+    V::DefaultType: Clone,
+    M: MapStorage<K::DefaultType, V::DefaultType>,
+{
+    const VIEW_VTABLE: &'static IMapView_abi<K, V> = &IMapView_abi(
+        Self::QueryInterface::<VIEW_VIEW>,
+        Self::AddRef::<VIEW_VIEW>,
+        Self::Release::<VIEW_VIEW>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::Lookup,
+        Self::GetSize,
+        Self::HasKey,
+        MapBox::<K, V, M>::Split,
+        PhantomData,
+        PhantomData,
+    );
+
+    const ITERABLE_VTABLE: &'static IIterable_abi<IKeyValuePair<K, V>> = &IIterable_abi(
+        Self::QueryInterface::<VIEW_ITERABLE>,
+        Self::AddRef::<VIEW_ITERABLE>,
+        Self::Release::<VIEW_ITERABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::First,
+        PhantomData,
+    );
+
+    // Returns the interface at `offset`, which owns the view's initial reference. The view takes
+    // ownership of a reference to `owner`.
+    fn create<I: Interface>(owner: *const MapBox<K, V, M>, offset: usize) -> I {
+        let this = Self::new(owner);
+        unsafe { std::mem::transmute_copy(&(*this).interface(offset)) }
+    }
+
+    // Returns the new view, which owns its initial reference and a reference to `owner`.
+    fn new(owner: *const MapBox<K, V, M>) -> *const Self {
+        Box::into_raw(Box::new(Self {
+            view_vtable: Self::VIEW_VTABLE,
+            iterable_vtable: Self::ITERABLE_VTABLE,
+            count: RefCount::new(),
+            owner,
+        }))
+    }
+
+    fn interface(&self, offset: usize) -> RawPtr {
+        unsafe { (&self.view_vtable as *const _ as *mut RawPtr).add(offset) as _ }
+    }
+
+    unsafe fn from_this<'a, const N: usize>(this: RawPtr) -> &'a Self {
+        &*((this as *mut RawPtr).sub(N) as *const Self)
+    }
+
+    // Returns the map's interface at `offset`, through which the view's methods are forwarded.
+    unsafe fn owner<const N: usize>(this: RawPtr, offset: usize) -> RawPtr {
+        (*Self::from_this::<N>(this).owner).interface(offset)
+    }
+
+    unsafe extern "system" fn QueryInterface<const N: usize>(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let this = Self::from_this::<N>(this);
+
+        let found = if iid == &<IMapView<K, V> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this.interface(VIEW_VIEW)
+        } else if iid == &<IIterable<IKeyValuePair<K, V>> as Interface>::IID {
+            this.interface(VIEW_ITERABLE)
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&this.count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef<const N: usize>(this: RawPtr) -> u32 {
+        Self::from_this::<N>(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release<const N: usize>(this: RawPtr) -> u32 {
+        let remaining = Self::from_this::<N>(this).count.release();
+
+        if remaining == 0 {
+            let this = Box::from_raw((this as *mut RawPtr).sub(N) as *mut Self);
+            MapBox::<K, V, M>::Release::<MAP>((*this.owner).interface(MAP));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IMapView`2", value)
+    }
+
+    unsafe extern "system" fn Lookup(this: RawPtr, key: K::Abi, result: *mut V::Abi) -> ErrorCode {
+        MapBox::<K, V, M>::Lookup::<MAP>(Self::owner::<VIEW_VIEW>(this, MAP), key, result)
+    }
+
+    unsafe extern "system" fn GetSize(this: RawPtr, result: *mut u32) -> ErrorCode {
+        MapBox::<K, V, M>::GetSize::<MAP>(Self::owner::<VIEW_VIEW>(this, MAP), result)
+    }
+
+    unsafe extern "system" fn HasKey(this: RawPtr, key: K::Abi, result: *mut bool) -> ErrorCode {
+        MapBox::<K, V, M>::HasKey::<MAP>(Self::owner::<VIEW_VIEW>(this, MAP), key, result)
+    }
+
+    unsafe extern "system" fn First(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        MapBox::<K, V, M>::First(Self::owner::<VIEW_ITERABLE>(this, ITERABLE), result)
+    }
+}
+
+// An `IIterator<IKeyValuePair<K, V>>` over a snapshot of a Rust-backed map's pairs that fails with
+// `E_CHANGED_STATE` once the map changes. The iterator holds a reference to the map, which it
+// reaches through functions rather than the map's type so that it needn't know the map's storage.
+#[repr(C)]
+struct MapIterator<K: RuntimeType + 'static, V: RuntimeType + 'static> {
+    vtable: *const IIterator_abi<IKeyValuePair<K, V>>,
+    count: RefCount,
+    owner: RawPtr,
+    version: fn(RawPtr) -> u32,
+    release: unsafe extern "system" fn(RawPtr) -> u32,
+    pairs: Vec<(K::DefaultType, V::DefaultType)>,
+    // The current index and the version of the map the iterator was created from.
+    state: Mutex<(u32, u32)>,
+}
+
+#[allow(non_snake_case)]
+impl<K: RuntimeType + 'static, V: RuntimeType + 'static> MapIterator<K, V>
+where
+    K::DefaultType: Clone,
+    V::DefaultType: Clone,
+{
+    const VTABLE: &'static IIterator_abi<IKeyValuePair<K, V>> = &IIterator_abi(
+        Self::QueryInterface,
+        Self::AddRef,
+        Self::Release,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetCurrent,
+        Self::GetHasCurrent,
+        Self::MoveNext,
+        Self::GetMany,
+        PhantomData,
+    );
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    // Calls `f` with the iterator's index, unless the map has changed.
+    fn with<F: FnOnce(&mut u32)>(&self, f: F) -> ErrorCode {
+        let mut state = self.state.lock().unwrap();
+
+        if (self.version)(self.owner) != state.1 {
+            return E_CHANGED_STATE;
+        }
+
+        f(&mut state.0);
+        ErrorCode::S_OK
+    }
+
+    unsafe fn write_pair(&self, index: usize, result: *mut RawPtr) {
+        let (key, value) = &self.pairs[index];
+        *result = KeyValuePair::<K, V>::create(key.clone(), value.clone());
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let found = if iid == &<IIterator<IKeyValuePair<K, V>> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&Self::from_this(this).count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        let remaining = Self::from_this(this).count.release();
+
+        if remaining == 0 {
+            let this = Box::from_raw(this as *mut Self);
+            (this.release)(this.owner);
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IIterator`1", value)
+    }
+
+    unsafe extern "system" fn GetCurrent(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let this = Self::from_this(this);
+        let mut code = ErrorCode::E_BOUNDS;
+
+        let changed = this.with(|index| {
+            if (*index as usize) < this.pairs.len() {
+                this.write_pair(*index as usize, result);
+                code = ErrorCode::S_OK;
+            }
+        });
+
+        if changed.is_err() {
+            changed
+        } else {
+            code
+        }
+    }
+
+    unsafe extern "system" fn GetHasCurrent(this: RawPtr, result: *mut bool) -> ErrorCode {
+        let this = Self::from_this(this);
+        this.with(|index| *result = (*index as usize) < this.pairs.len())
+    }
+
+    unsafe extern "system" fn MoveNext(this: RawPtr, result: *mut bool) -> ErrorCode {
+        let this = Self::from_this(this);
+
+        this.with(|index| {
+            if (*index as usize) < this.pairs.len() {
+                *index += 1;
+            }
+
+            *result = (*index as usize) < this.pairs.len();
+        })
+    }
+
+    unsafe extern "system" fn GetMany(
+        this: RawPtr,
+        capacity: u32,
+        items: *mut RawPtr,
+        result: *mut u32,
+    ) -> ErrorCode {
+        let this = Self::from_this(this);
+
+        this.with(|index| {
+            let start = (*index as usize).min(this.pairs.len());
+            let copied = (this.pairs.len() - start).min(capacity as usize);
+
+            for offset in 0..copied {
+                this.write_pair(start + offset, items.add(offset));
+            }
+
+            *result = copied as u32;
+            *index += copied as u32;
+        })
+    }
+}
+
+// An immutable `IKeyValuePair<K, V>` returned by a Rust-backed map's iterator.
+#[repr(C)]
+struct KeyValuePair<K: RuntimeType + 'static, V: RuntimeType + 'static> {
+    vtable: *const IKeyValuePair_abi<K, V>,
+    count: RefCount,
+    key: K::DefaultType,
+    value: V::DefaultType,
+}
+
+#[allow(non_snake_case)]
+impl<K: RuntimeType + 'static, V: RuntimeType + 'static> KeyValuePair<K, V>
+where
+    K::DefaultType: Clone,
+    V::DefaultType: Clone,
+{
+    const VTABLE: &'static IKeyValuePair_abi<K, V> = &IKeyValuePair_abi(
+        Self::QueryInterface,
+        Self::AddRef,
+        Self::Release,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetKey,
+        Self::GetValue,
+        PhantomData,
+        PhantomData,
+    );
+
+    fn create(key: K::DefaultType, value: V::DefaultType) -> RawPtr {
+        Box::into_raw(Box::new(Self {
+            vtable: Self::VTABLE,
+            count: RefCount::new(),
+            key,
+            value,
+        })) as _
+    }
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let found = if iid == &<IKeyValuePair<K, V> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&Self::from_this(this).count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        let remaining = Self::from_this(this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut Self));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IKeyValuePair`2", value)
+    }
+
+    unsafe extern "system" fn GetKey(this: RawPtr, result: *mut K::Abi) -> ErrorCode {
+        write_abi::<K>(result, &Self::from_this(this).key);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetValue(this: RawPtr, result: *mut V::Abi) -> ErrorCode {
+        write_abi::<V>(result, &Self::from_this(this).value);
+        ErrorCode::S_OK
+    }
+}
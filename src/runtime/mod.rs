@@ -1,16 +1,32 @@
 mod array;
+mod async_future;
+mod async_operation;
+mod async_progress;
+mod chunk;
+mod collection;
 mod delay_load;
+mod event_revoker;
+mod event_stream;
 mod factory_cache;
 mod guid;
 mod heap;
+mod map;
 mod numerics;
 mod param;
 mod ref_count;
 mod time_span;
+mod unwind;
+mod vector;
 mod waiter;
 
 pub use array::*;
+pub use async_future::*;
+pub use async_operation::*;
+pub use async_progress::*;
+pub use chunk::*;
 pub use delay_load::*;
+pub use event_revoker::*;
+pub use event_stream::*;
 pub use factory_cache::*;
 pub use guid::*;
 pub use heap::*;
@@ -18,4 +34,5 @@ pub use numerics::*;
 pub use param::*;
 pub use ref_count::*;
 pub use time_span::*;
+pub use unwind::*;
 pub use waiter::*;
@@ -0,0 +1,9 @@
+/// Runs a Rust callback that was invoked from across an FFI boundary. Unwinding out of an
+/// `extern "system"` function is undefined behavior, so a panic inside `callback` aborts the
+/// process instead. This is used by the generated bindings and should not be used directly.
+pub fn abort_on_unwind<F: FnOnce() -> R, R>(callback: F) -> R {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(callback)) {
+        Ok(result) => result,
+        Err(_) => std::process::abort(),
+    }
+}
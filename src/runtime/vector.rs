@@ -0,0 +1,762 @@
+use super::collection::*;
+use crate::foundation::collections::{
+    CollectionChange, IIterable, IIterable_abi, IIterator, IIterator_abi, IObservableVector,
+    IObservableVector_abi, IVector, IVectorView, IVectorView_abi, IVector_abi,
+};
+use crate::foundation::EventRegistrationToken;
+use crate::*;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+// A Rust-backed vector implementing `IVector<T>`, `IVectorView<T>`, `IIterable<T>` and
+// `IObservableVector<T>`, created by converting a `Vec` into `IVector<T>` or `IObservableVector<T>`.
+// A `Vec` converted into one of the read-only interfaces is wrapped in a `VectorViewBox`. Each of
+// the object's interface pointers points at its own vtable field so the methods of the interface
+// at offset `N` recover the object with `from_this::<N>`.
+#[repr(C)]
+struct VectorBox<T: RuntimeType + 'static> {
+    vector_vtable: *const IVector_abi<T>,
+    view_vtable: *const IVectorView_abi<T>,
+    iterable_vtable: *const IIterable_abi<T>,
+    observable_vtable: *const IObservableVector_abi<T>,
+    count: RefCount,
+    state: Mutex<VectorState<T>>,
+}
+
+struct VectorState<T: RuntimeType> {
+    values: Vec<T::DefaultType>,
+    // Incremented by every change so that outstanding iterators can tell they're invalid.
+    version: u32,
+    handlers: ChangedHandlers,
+}
+
+const VECTOR: usize = 0;
+const VIEW: usize = 1;
+const ITERABLE: usize = 2;
+const OBSERVABLE: usize = 3;
+
+impl<T: RuntimeType + 'static> From<Vec<T::DefaultType>> for IVector<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from(values: Vec<T::DefaultType>) -> Self {
+        VectorBox::<T>::create(values, VECTOR)
+    }
+}
+
+impl<T: RuntimeType + 'static> From<Vec<T::DefaultType>> for IVectorView<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from(values: Vec<T::DefaultType>) -> Self {
+        VectorViewBox::<T>::create(VectorBox::new(values), VIEW_VIEW)
+    }
+}
+
+impl<T: RuntimeType + 'static> From<Vec<T::DefaultType>> for IIterable<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from(values: Vec<T::DefaultType>) -> Self {
+        VectorViewBox::<T>::create(VectorBox::new(values), VIEW_ITERABLE)
+    }
+}
+
+impl<T: RuntimeType + 'static> From<Vec<T::DefaultType>> for IObservableVector<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from(values: Vec<T::DefaultType>) -> Self {
+        VectorBox::<T>::create(values, OBSERVABLE)
+    }
+}
+
+impl<T: RuntimeType + 'static> std::iter::FromIterator<T::DefaultType> for IVector<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T::DefaultType>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: RuntimeType + 'static> std::iter::FromIterator<T::DefaultType> for IVectorView<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T::DefaultType>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: RuntimeType + 'static> std::iter::FromIterator<T::DefaultType> for IIterable<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T::DefaultType>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+impl<T: RuntimeType + 'static> std::iter::FromIterator<T::DefaultType> for IObservableVector<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    fn from_iter<I: IntoIterator<Item = T::DefaultType>>(iter: I) -> Self {
+        iter.into_iter().collect::<Vec<_>>().into()
+    }
+}
+
+#[allow(non_snake_case)]
+impl<T: RuntimeType + 'static> VectorBox<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    const VECTOR_VTABLE: &'static IVector_abi<T> = &IVector_abi(
+        Self::QueryInterface::<VECTOR>,
+        Self::AddRef::<VECTOR>,
+        Self::Release::<VECTOR>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetAt::<VECTOR>,
+        Self::GetSize::<VECTOR>,
+        Self::GetView,
+        Self::IndexOf::<VECTOR>,
+        Self::SetAt,
+        Self::InsertAt,
+        Self::RemoveAt,
+        Self::Append,
+        Self::RemoveAtEnd,
+        Self::Clear,
+        Self::GetMany::<VECTOR>,
+        Self::ReplaceAll,
+        PhantomData,
+    );
+
+    const VIEW_VTABLE: &'static IVectorView_abi<T> = &IVectorView_abi(
+        Self::QueryInterface::<VIEW>,
+        Self::AddRef::<VIEW>,
+        Self::Release::<VIEW>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetAt::<VIEW>,
+        Self::GetSize::<VIEW>,
+        Self::IndexOf::<VIEW>,
+        Self::GetMany::<VIEW>,
+        PhantomData,
+    );
+
+    const ITERABLE_VTABLE: &'static IIterable_abi<T> = &IIterable_abi(
+        Self::QueryInterface::<ITERABLE>,
+        Self::AddRef::<ITERABLE>,
+        Self::Release::<ITERABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::First,
+        PhantomData,
+    );
+
+    const OBSERVABLE_VTABLE: &'static IObservableVector_abi<T> = &IObservableVector_abi(
+        Self::QueryInterface::<OBSERVABLE>,
+        Self::AddRef::<OBSERVABLE>,
+        Self::Release::<OBSERVABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::AddVectorChanged,
+        Self::RemoveVectorChanged,
+        PhantomData,
+    );
+
+    // Returns the interface at `offset`, which owns the object's initial reference.
+    fn create<I: Interface>(values: Vec<T::DefaultType>, offset: usize) -> I {
+        let this = Self::new(values);
+        unsafe { std::mem::transmute_copy(&(*this).interface(offset)) }
+    }
+
+    // Returns the new object, which owns its initial reference.
+    fn new(values: Vec<T::DefaultType>) -> *const Self {
+        Box::into_raw(Box::new(Self {
+            vector_vtable: Self::VECTOR_VTABLE,
+            view_vtable: Self::VIEW_VTABLE,
+            iterable_vtable: Self::ITERABLE_VTABLE,
+            observable_vtable: Self::OBSERVABLE_VTABLE,
+            count: RefCount::new(),
+            state: Mutex::new(VectorState {
+                values,
+                version: 0,
+                handlers: ChangedHandlers::default(),
+            }),
+        }))
+    }
+
+    fn interface(&self, offset: usize) -> RawPtr {
+        unsafe { (&self.vector_vtable as *const _ as *mut RawPtr).add(offset) as _ }
+    }
+
+    unsafe fn from_this<'a, const N: usize>(this: RawPtr) -> &'a Self {
+        &*((this as *mut RawPtr).sub(N) as *const Self)
+    }
+
+    // Applies a change to the vector, invalidating any iterators and raising the `VectorChanged`
+    // event if the change succeeds.
+    fn change<F>(&self, change: CollectionChange, f: F) -> ErrorCode
+    where
+        F: FnOnce(&mut Vec<T::DefaultType>) -> std::result::Result<u32, ErrorCode>,
+    {
+        let (index, handlers) = {
+            let mut state = self.state.lock().unwrap();
+
+            let index = match f(&mut state.values) {
+                Ok(index) => index,
+                Err(code) => return code,
+            };
+
+            state.version = state.version.wrapping_add(1);
+            (index, state.handlers.snapshot())
+        };
+
+        if !handlers.is_empty() {
+            let args = VectorChangedEventArgs::create(change, index);
+            invoke_changed(handlers, self.interface(OBSERVABLE), &args);
+        }
+
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn QueryInterface<const N: usize>(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let this = Self::from_this::<N>(this);
+
+        let found = if iid == &<IVector<T> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this.interface(VECTOR)
+        } else if iid == &<IVectorView<T> as Interface>::IID {
+            this.interface(VIEW)
+        } else if iid == &<IIterable<T> as Interface>::IID {
+            this.interface(ITERABLE)
+        } else if iid == &<IObservableVector<T> as Interface>::IID {
+            this.interface(OBSERVABLE)
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&this.count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef<const N: usize>(this: RawPtr) -> u32 {
+        Self::from_this::<N>(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release<const N: usize>(this: RawPtr) -> u32 {
+        let remaining = Self::from_this::<N>(this).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw((this as *mut RawPtr).sub(N) as *mut Self));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IVector`1", value)
+    }
+
+    unsafe extern "system" fn GetAt<const N: usize>(
+        this: RawPtr,
+        index: u32,
+        result: *mut T::Abi,
+    ) -> ErrorCode {
+        let state = Self::from_this::<N>(this).state.lock().unwrap();
+
+        match state.values.get(index as usize) {
+            Some(value) => {
+                write_abi::<T>(result, value);
+                ErrorCode::S_OK
+            }
+            None => ErrorCode::E_BOUNDS,
+        }
+    }
+
+    unsafe extern "system" fn GetSize<const N: usize>(this: RawPtr, result: *mut u32) -> ErrorCode {
+        *result = Self::from_this::<N>(this)
+            .state
+            .lock()
+            .unwrap()
+            .values
+            .len() as u32;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn GetView(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let this = Self::from_this::<VECTOR>(this);
+        this.count.add_ref();
+        *result = (*VectorViewBox::new(this)).interface(VIEW_VIEW);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn IndexOf<const N: usize>(
+        this: RawPtr,
+        value: T::Abi,
+        index: *mut u32,
+        result: *mut bool,
+    ) -> ErrorCode {
+        let state = Self::from_this::<N>(this).state.lock().unwrap();
+        let value = abi_ref::<T>(&value);
+
+        match state.values.iter().position(|item| item == value) {
+            Some(position) => {
+                *index = position as u32;
+                *result = true;
+            }
+            None => {
+                *index = 0;
+                *result = false;
+            }
+        }
+
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn SetAt(this: RawPtr, index: u32, value: T::Abi) -> ErrorCode {
+        let value = abi_ref::<T>(&value).clone();
+
+        Self::from_this::<VECTOR>(this).change(CollectionChange::ItemChanged, |values| match values
+            .get_mut(index as usize)
+        {
+            Some(item) => {
+                *item = value;
+                Ok(index)
+            }
+            None => Err(ErrorCode::E_BOUNDS),
+        })
+    }
+
+    unsafe extern "system" fn InsertAt(this: RawPtr, index: u32, value: T::Abi) -> ErrorCode {
+        let value = abi_ref::<T>(&value).clone();
+
+        Self::from_this::<VECTOR>(this).change(CollectionChange::ItemInserted, |values| {
+            if index as usize > values.len() {
+                return Err(ErrorCode::E_BOUNDS);
+            }
+
+            values.insert(index as usize, value);
+            Ok(index)
+        })
+    }
+
+    unsafe extern "system" fn RemoveAt(this: RawPtr, index: u32) -> ErrorCode {
+        Self::from_this::<VECTOR>(this).change(CollectionChange::ItemRemoved, |values| {
+            if index as usize >= values.len() {
+                return Err(ErrorCode::E_BOUNDS);
+            }
+
+            values.remove(index as usize);
+            Ok(index)
+        })
+    }
+
+    unsafe extern "system" fn Append(this: RawPtr, value: T::Abi) -> ErrorCode {
+        let value = abi_ref::<T>(&value).clone();
+
+        Self::from_this::<VECTOR>(this).change(CollectionChange::ItemInserted, |values| {
+            values.push(value);
+            Ok(values.len() as u32 - 1)
+        })
+    }
+
+    unsafe extern "system" fn RemoveAtEnd(this: RawPtr) -> ErrorCode {
+        Self::from_this::<VECTOR>(this).change(CollectionChange::ItemRemoved, |values| match values
+            .pop()
+        {
+            Some(_) => Ok(values.len() as u32),
+            None => Err(ErrorCode::E_BOUNDS),
+        })
+    }
+
+    unsafe extern "system" fn Clear(this: RawPtr) -> ErrorCode {
+        Self::from_this::<VECTOR>(this).change(CollectionChange::Reset, |values| {
+            values.clear();
+            Ok(0)
+        })
+    }
+
+    unsafe extern "system" fn GetMany<const N: usize>(
+        this: RawPtr,
+        start: u32,
+        capacity: u32,
+        items: *mut T::Abi,
+        result: *mut u32,
+    ) -> ErrorCode {
+        let state = Self::from_this::<N>(this).state.lock().unwrap();
+        *result = copy_many::<T>(&state.values, start as usize, capacity, items);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn ReplaceAll(
+        this: RawPtr,
+        count: u32,
+        items: *const T::Abi,
+    ) -> ErrorCode {
+        let items = if count == 0 {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(items, count as usize)
+                .iter()
+                .map(|item| abi_ref::<T>(item).clone())
+                .collect()
+        };
+
+        Self::from_this::<VECTOR>(this).change(CollectionChange::Reset, |values| {
+            *values = items;
+            Ok(0)
+        })
+    }
+
+    unsafe extern "system" fn First(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        let this = Self::from_this::<ITERABLE>(this);
+        this.count.add_ref();
+
+        let version = this.state.lock().unwrap().version;
+
+        let iterator = Box::into_raw(Box::new(VectorIterator {
+            vtable: VectorIterator::<T>::VTABLE,
+            count: RefCount::new(),
+            owner: this,
+            state: Mutex::new((0, version)),
+        }));
+
+        *result = iterator as _;
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn AddVectorChanged(
+        this: RawPtr,
+        handler: RawPtr,
+        token: *mut EventRegistrationToken,
+    ) -> ErrorCode {
+        let this = Self::from_this::<OBSERVABLE>(this);
+        this.state.lock().unwrap().handlers.add(handler, token)
+    }
+
+    unsafe extern "system" fn RemoveVectorChanged(
+        this: RawPtr,
+        token: EventRegistrationToken,
+    ) -> ErrorCode {
+        let this = Self::from_this::<OBSERVABLE>(this);
+        this.state.lock().unwrap().handlers.remove(token)
+    }
+}
+
+// A read-only view of a Rust-backed vector implementing `IVectorView<T>` and `IIterable<T>`,
+// returned by `GetView` or created by converting a `Vec` into either of those interfaces. The view
+// is an object of its own so that it can't be cast back to the vector's other interfaces. It holds
+// a reference to the vector and forwards its methods to the vector's.
+#[repr(C)]
+struct VectorViewBox<T: RuntimeType + 'static> {
+    view_vtable: *const IVectorView_abi<T>,
+    iterable_vtable: *const IIterable_abi<T>,
+    count: RefCount,
+    owner: *const VectorBox<T>,
+}
+
+const VIEW_VIEW: usize = 0;
+const VIEW_ITERABLE: usize = 1;
+
+#[allow(non_snake_case)]
+impl<T: RuntimeType + 'static> VectorViewBox<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    const VIEW_VTABLE: &'static IVectorView_abi<T> = &IVectorView_abi(
+        Self::QueryInterface::<VIEW_VIEW>,
+        Self::AddRef::<VIEW_VIEW>,
+        Self::Release::<VIEW_VIEW>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetAt,
+        Self::GetSize,
+        Self::IndexOf,
+        Self::GetMany,
+        PhantomData,
+    );
+
+    const ITERABLE_VTABLE: &'static IIterable_abi<T> = &IIterable_abi(
+        Self::QueryInterface::<VIEW_ITERABLE>,
+        Self::AddRef::<VIEW_ITERABLE>,
+        Self::Release::<VIEW_ITERABLE>,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::First,
+        PhantomData,
+    );
+
+    // Returns the interface at `offset`, which owns the view's initial reference. The view takes
+    // ownership of a reference to `owner`.
+    fn create<I: Interface>(owner: *const VectorBox<T>, offset: usize) -> I {
+        let this = Self::new(owner);
+        unsafe { std::mem::transmute_copy(&(*this).interface(offset)) }
+    }
+
+    // Returns the new view, which owns its initial reference and a reference to `owner`.
+    fn new(owner: *const VectorBox<T>) -> *const Self {
+        Box::into_raw(Box::new(Self {
+            view_vtable: Self::VIEW_VTABLE,
+            iterable_vtable: Self::ITERABLE_VTABLE,
+            count: RefCount::new(),
+            owner,
+        }))
+    }
+
+    fn interface(&self, offset: usize) -> RawPtr {
+        unsafe { (&self.view_vtable as *const _ as *mut RawPtr).add(offset) as _ }
+    }
+
+    unsafe fn from_this<'a, const N: usize>(this: RawPtr) -> &'a Self {
+        &*((this as *mut RawPtr).sub(N) as *const Self)
+    }
+
+    // Returns the vector's interface at `offset`, through which the view's methods are forwarded.
+    unsafe fn owner<const N: usize>(this: RawPtr, offset: usize) -> RawPtr {
+        (*Self::from_this::<N>(this).owner).interface(offset)
+    }
+
+    unsafe extern "system" fn QueryInterface<const N: usize>(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let this = Self::from_this::<N>(this);
+
+        let found = if iid == &<IVectorView<T> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this.interface(VIEW_VIEW)
+        } else if iid == &<IIterable<T> as Interface>::IID {
+            this.interface(VIEW_ITERABLE)
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&this.count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef<const N: usize>(this: RawPtr) -> u32 {
+        Self::from_this::<N>(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release<const N: usize>(this: RawPtr) -> u32 {
+        let remaining = Self::from_this::<N>(this).count.release();
+
+        if remaining == 0 {
+            let this = Box::from_raw((this as *mut RawPtr).sub(N) as *mut Self);
+            VectorBox::<T>::Release::<VECTOR>((*this.owner).interface(VECTOR));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IVectorView`1", value)
+    }
+
+    unsafe extern "system" fn GetAt(this: RawPtr, index: u32, result: *mut T::Abi) -> ErrorCode {
+        VectorBox::<T>::GetAt::<VECTOR>(Self::owner::<VIEW_VIEW>(this, VECTOR), index, result)
+    }
+
+    unsafe extern "system" fn GetSize(this: RawPtr, result: *mut u32) -> ErrorCode {
+        VectorBox::<T>::GetSize::<VECTOR>(Self::owner::<VIEW_VIEW>(this, VECTOR), result)
+    }
+
+    unsafe extern "system" fn IndexOf(
+        this: RawPtr,
+        value: T::Abi,
+        index: *mut u32,
+        result: *mut bool,
+    ) -> ErrorCode {
+        VectorBox::<T>::IndexOf::<VECTOR>(
+            Self::owner::<VIEW_VIEW>(this, VECTOR),
+            value,
+            index,
+            result,
+        )
+    }
+
+    unsafe extern "system" fn GetMany(
+        this: RawPtr,
+        start: u32,
+        capacity: u32,
+        items: *mut T::Abi,
+        result: *mut u32,
+    ) -> ErrorCode {
+        VectorBox::<T>::GetMany::<VECTOR>(
+            Self::owner::<VIEW_VIEW>(this, VECTOR),
+            start,
+            capacity,
+            items,
+            result,
+        )
+    }
+
+    unsafe extern "system" fn First(this: RawPtr, result: *mut RawPtr) -> ErrorCode {
+        VectorBox::<T>::First(Self::owner::<VIEW_ITERABLE>(this, ITERABLE), result)
+    }
+}
+
+// Copies up to `capacity` values starting at `start` to `items`, returning the number copied.
+unsafe fn copy_many<T: RuntimeType>(
+    values: &[T::DefaultType],
+    start: usize,
+    capacity: u32,
+    items: *mut T::Abi,
+) -> u32
+where
+    T::DefaultType: Clone,
+{
+    let values = values.get(start..).unwrap_or_default();
+    let copied = values.len().min(capacity as usize);
+
+    for (offset, value) in values[..copied].iter().enumerate() {
+        write_abi::<T>(items.add(offset), value);
+    }
+
+    copied as u32
+}
+
+// An `IIterator<T>` over a Rust-backed vector that fails with `E_CHANGED_STATE` once the vector
+// changes. The iterator holds a reference to the vector.
+#[repr(C)]
+struct VectorIterator<T: RuntimeType + 'static> {
+    vtable: *const IIterator_abi<T>,
+    count: RefCount,
+    owner: *const VectorBox<T>,
+    // The current index and the version of the vector the iterator was created from.
+    state: Mutex<(u32, u32)>,
+}
+
+#[allow(non_snake_case)]
+impl<T: RuntimeType + 'static> VectorIterator<T>
+where
+    T::DefaultType: Clone + PartialEq,
+{
+    const VTABLE: &'static IIterator_abi<T> = &IIterator_abi(
+        Self::QueryInterface,
+        Self::AddRef,
+        Self::Release,
+        GetIids,
+        Self::GetRuntimeClassName,
+        GetTrustLevel,
+        Self::GetCurrent,
+        Self::GetHasCurrent,
+        Self::MoveNext,
+        Self::GetMany,
+        PhantomData,
+    );
+
+    unsafe fn from_this<'a>(this: RawPtr) -> &'a Self {
+        &*(this as *const Self)
+    }
+
+    // Calls `f` with the iterator's index and the vector's values, unless the vector has changed.
+    fn with<F: FnOnce(&mut u32, &[T::DefaultType])>(&self, f: F) -> ErrorCode {
+        let mut state = self.state.lock().unwrap();
+        let owner = unsafe { (*self.owner).state.lock().unwrap() };
+
+        if owner.version != state.1 {
+            return E_CHANGED_STATE;
+        }
+
+        f(&mut state.0, &owner.values);
+        ErrorCode::S_OK
+    }
+
+    unsafe extern "system" fn QueryInterface(
+        this: RawPtr,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        let found = if iid == &<IIterator<T> as Interface>::IID
+            || iid == &<IUnknown as Interface>::IID
+            || iid == &<Object as Interface>::IID
+        {
+            this
+        } else {
+            std::ptr::null_mut()
+        };
+
+        query_result(&Self::from_this(this).count, found, interface)
+    }
+
+    unsafe extern "system" fn AddRef(this: RawPtr) -> u32 {
+        Self::from_this(this).count.add_ref()
+    }
+
+    unsafe extern "system" fn Release(this: RawPtr) -> u32 {
+        let remaining = Self::from_this(this).count.release();
+
+        if remaining == 0 {
+            let this = Box::from_raw(this as *mut Self);
+            VectorBox::<T>::Release::<VECTOR>((*this.owner).interface(VECTOR));
+        }
+
+        remaining
+    }
+
+    unsafe extern "system" fn GetRuntimeClassName(_: RawPtr, value: *mut RawPtr) -> ErrorCode {
+        runtime_class_name("Windows.Foundation.Collections.IIterator`1", value)
+    }
+
+    unsafe extern "system" fn GetCurrent(this: RawPtr, result: *mut T::Abi) -> ErrorCode {
+        let mut code = ErrorCode::E_BOUNDS;
+
+        let changed = Self::from_this(this).with(|index, values| {
+            if let Some(value) = values.get(*index as usize) {
+                write_abi::<T>(result, value);
+                code = ErrorCode::S_OK;
+            }
+        });
+
+        if changed.is_err() {
+            changed
+        } else {
+            code
+        }
+    }
+
+    unsafe extern "system" fn GetHasCurrent(this: RawPtr, result: *mut bool) -> ErrorCode {
+        Self::from_this(this).with(|index, values| *result = (*index as usize) < values.len())
+    }
+
+    unsafe extern "system" fn MoveNext(this: RawPtr, result: *mut bool) -> ErrorCode {
+        Self::from_this(this).with(|index, values| {
+            if (*index as usize) < values.len() {
+                *index += 1;
+            }
+
+            *result = (*index as usize) < values.len();
+        })
+    }
+
+    unsafe extern "system" fn GetMany(
+        this: RawPtr,
+        capacity: u32,
+        items: *mut T::Abi,
+        result: *mut u32,
+    ) -> ErrorCode {
+        Self::from_this(this).with(|index, values| {
+            *result = copy_many::<T>(values, *index as usize, capacity, items);
+            *index += *result;
+        })
+    }
+}
@@ -171,6 +171,27 @@ impl PartialEq for HString {
     }
 }
 
+impl Eq for HString {}
+
+impl std::hash::Hash for HString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_wide().hash(state)
+    }
+}
+
+// Strings are ordered by their UTF-16 code units, as `WindowsCompareStringOrdinal` orders them.
+impl PartialOrd for HString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_wide().cmp(other.as_wide())
+    }
+}
+
 impl PartialEq<String> for HString {
     fn eq(&self, other: &String) -> bool {
         self == other.as_str()